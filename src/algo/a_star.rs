@@ -1,44 +1,56 @@
-use crate::global::types::plan::AStarPlan;
+use crate::global::types::plan::{Plan, SearchStats, VoxelPlan, VolumePlanner};
 use std::fmt::Debug;
-use crate::global::types::plan::Planner;
-use crate::global::types::{ACoord, Belief, HeurHeap, HeurNode, SpatialMap};
-use std::collections::{HashMap, HashSet};
+use crate::global::types::plan::{CellChange, Planner};
+use crate::global::types::{ACoord, Belief, HeurHeap, HeurNode, SpatialMap, VCoord, VolumetricMap};
+use crate::global::types::{SearchGraph, Surface, Volume};
+use std::collections::HashMap;
+use std::time::Instant;
 
 pub struct AStarPlanner;
 
 impl AStarPlanner {
-    fn encode_plan<S: SpatialMap>(
+    /// Precursors of the nodes settled up to the target, shared by flat and layered maps
+    fn encode_plan<G: SearchGraph>(
         &self,
-        env: &S,
-        source: S::Encoded,
-        target: S::Encoded,
-    ) -> Option<HashMap<S::Encoded, S::Encoded>>
-        where S::Encoded: Debug
-    {
-        println!("planning");
-        let mut p_queue: HeurHeap<S::Encoded> = HeurHeap::new();
-        let mut enqueue: HashSet<S::Encoded> = HashSet::new();
+        env: &G,
+        source: G::Node,
+        target: G::Node,
+        stats: &mut SearchStats,
+    ) -> Option<HashMap<G::Node, G::Node>> {
+        let mut p_queue: HeurHeap<G::Node> = HeurHeap::new();
+        let mut incurred: HashMap<G::Node, usize> = HashMap::new();
         let mut precursor = HashMap::new();
         p_queue.push(HeurNode {
             incurred: 0,
             cost: env.distance(source, target),
             coord: source,
         });
-        enqueue.insert(source);
+        incurred.insert(source, 0);
+        stats.pushes += 1;
         while let Some(node) = p_queue.pop() {
             if node.coord == target {
                 return Some(precursor);
             }
+            if node.incurred > incurred[&node.coord] {
+                // superseded by a cheaper route found after it was queued
+                continue;
+            }
+            stats.expanded += 1;
             for n_xy in env.neighbors(node.coord) {
-                if enqueue.insert(n_xy) && env.belief(n_xy) != Belief::Occupied {
+                if env.belief(n_xy) == Belief::Occupied {
+                    continue;
+                }
+                let cost = node.incurred + env.distance(node.coord, n_xy);
+                if incurred.get(&n_xy).is_none_or(|&c| cost < c) {
+                    incurred.insert(n_xy, cost);
                     precursor.insert(n_xy, node.coord);
                     let heuristic = env.distance(n_xy, target);
-                    let incurred = node.incurred + env.distance(node.coord, n_xy);
                     p_queue.push(HeurNode {
-                        incurred,
-                        cost: incurred + heuristic,
+                        incurred: cost,
+                        cost: cost + heuristic,
                         coord: n_xy,
                     });
+                    stats.pushes += 1;
                 }
             }
         }
@@ -68,21 +80,44 @@ impl<S: SpatialMap> Planner<S> for AStarPlanner
 
 where S::Encoded: Debug
 {
-    type Plan = AStarPlan;
-    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Self::Plan> {
+    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Plan> {
         if env.obstructed(target) {
             return None;
         };
+        let start = Instant::now();
         let (s_encode, t_encode) = (env.encode(source), env.encode(target));
-
-        let precursor = self.encode_plan(env, s_encode, t_encode);
+        let mut stats = SearchStats::default();
+        let precursor = self.encode_plan(&Surface(env), s_encode, t_encode, &mut stats);
         match precursor {
             Some(map) => {
-                let plan = self.reconstruct_decode(env, &map, s_encode, t_encode);
-                Some(Self::Plan { plan })
+                let mut plan = self.reconstruct_decode(env, &map, s_encode, t_encode);
+                plan.reverse();
+                stats.elapsed = start.elapsed();
+                Some(Plan::new(env, source, plan, stats))
             }
             None => None,
         }
     }
-    fn update(&mut self, _: &S, _: ACoord, _: ACoord) {}
+    fn update(&mut self, _: &S, _: &[CellChange]) {}
+}
+
+impl<V: VolumetricMap> VolumePlanner<V> for AStarPlanner {
+    fn plan_volume(&mut self, env: &V, source: VCoord, target: VCoord) -> Option<VoxelPlan> {
+        if env.obstructed(target) {
+            return None;
+        };
+        let start = Instant::now();
+        let (s_encode, t_encode) = (env.encode(source), env.encode(target));
+        let mut stats = SearchStats::default();
+        let precursor = self.encode_plan(&Volume(env), s_encode, t_encode, &mut stats)?;
+        let mut plan = vec![];
+        let mut node = t_encode;
+        while node != s_encode {
+            plan.push(env.decode(node));
+            node = precursor[&node];
+        }
+        plan.reverse();
+        stats.elapsed = start.elapsed();
+        Some(VoxelPlan::new(env, source, plan, stats))
+    }
 }
@@ -0,0 +1,288 @@
+use crate::global::types::plan::{CellChange, Plan, Planner, Provenance, SearchStats};
+use crate::global::types::{ACoord, Belief, HeurHeap, HeurNode, SpatialMap};
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// Smallest inflation removed per iteration, bounding the iterations down to 1
+pub const MIN_DECREMENT: f64 = 0.1;
+
+/// Work a single call to plan may spend searching
+///
+/// # Attributes #
+/// * expansions := nodes expanded before the call returns
+/// * time := wall clock before the call returns
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Budget {
+    pub expansions: Option<usize>,
+    pub time: Option<Duration>,
+}
+
+impl Budget {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+    pub fn expansions(expansions: usize) -> Self {
+        Self {
+            expansions: Some(expansions),
+            time: None,
+        }
+    }
+    pub fn time(time: Duration) -> Self {
+        Self {
+            expansions: None,
+            time: Some(time),
+        }
+    }
+}
+
+/// Search tree rooted at the target, kept between calls
+struct Search<E> {
+    source: E,
+    target: E,
+    epsilon: f64,
+    g: HashMap<E, usize>,
+    successor: HashMap<E, E>,
+    open: HashSet<E>,
+    closed: HashSet<E>,
+    incons: HashSet<E>,
+    heap: HeurHeap<E>,
+    // epsilon of the last iteration run to completion
+    solved: Option<f64>,
+}
+
+/// Anytime Repairing A*
+///
+/// Weighted A* with an inflated heuristic finds a first plan quickly, each
+/// later call lowers the inflation and repairs the previous search instead of
+/// starting over, until the plan is optimal. Searches run from the target so
+/// the tree stays valid while the source moves along the plan. Map updates
+/// discard the search.
+///
+/// The budget covers the first search as well, a call which runs out before
+/// the source is reached returns none and the next call resumes the search.
+/// Every plan reports the bound on its suboptimality.
+///
+/// # Attributes #
+/// * epsilon := initial inflation of the heuristic, at least 1
+/// * decrement := inflation removed after every completed iteration, at least MIN_DECREMENT
+/// * budget := work spent per call
+pub struct AraStarPlanner<S: SpatialMap> {
+    pub epsilon: f64,
+    pub decrement: f64,
+    pub budget: Budget,
+    search: Option<Search<S::Encoded>>,
+}
+
+impl<S: SpatialMap> AraStarPlanner<S>
+where
+    S::Encoded: Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            epsilon: 2.5,
+            decrement: 0.5,
+            budget: Budget::unlimited(),
+            search: None,
+        }
+    }
+    pub fn with_epsilon(mut self, epsilon: f64) -> Self {
+        self.epsilon = epsilon.max(1.0);
+        self
+    }
+    pub fn with_decrement(mut self, decrement: f64) -> Self {
+        self.decrement = decrement.max(MIN_DECREMENT);
+        self
+    }
+    pub fn with_budget(mut self, budget: Budget) -> Self {
+        self.budget = budget;
+        self
+    }
+    fn initialize(
+        &mut self,
+        env: &S,
+        source: S::Encoded,
+        target: S::Encoded,
+        stats: &mut SearchStats,
+    ) {
+        let mut search = Search {
+            source,
+            target,
+            epsilon: self.epsilon,
+            g: HashMap::new(),
+            successor: HashMap::new(),
+            open: HashSet::new(),
+            closed: HashSet::new(),
+            incons: HashSet::new(),
+            heap: HeurHeap::new(),
+            solved: None,
+        };
+        search.g.insert(target, 0);
+        search.open.insert(target);
+        Self::reorder(env, &mut search, stats);
+        self.search = Some(search);
+    }
+    fn key(env: &S, search: &Search<S::Encoded>, node: S::Encoded, incurred: usize) -> usize {
+        let h = env.distance(node, search.source) as f64;
+        incurred.saturating_add((search.epsilon * h) as usize)
+    }
+    /// Rebuilds the open list after the inflation or the source changed
+    fn reorder(env: &S, search: &mut Search<S::Encoded>, stats: &mut SearchStats) {
+        search.heap.clear();
+        for &node in &search.open {
+            let incurred = search.g[&node];
+            search.heap.push(HeurNode {
+                cost: Self::key(env, search, node, incurred),
+                incurred,
+                coord: node,
+            });
+        }
+        stats.pushes += search.open.len();
+    }
+    /// Starts an iteration, inconsistent nodes are reopened
+    fn restart(env: &S, search: &mut Search<S::Encoded>, stats: &mut SearchStats) {
+        let incons = std::mem::take(&mut search.incons);
+        search.open.extend(incons);
+        search.closed.clear();
+        Self::reorder(env, search, stats);
+    }
+    /// Expands nodes until the source is within epsilon of optimal, false once out of budget
+    fn improve_path(
+        &self,
+        env: &S,
+        search: &mut Search<S::Encoded>,
+        start: Instant,
+        stats: &mut SearchStats,
+    ) -> bool {
+        while let Some(node) = search.heap.pop() {
+            if !search.open.contains(&node.coord) || node.incurred != search.g[&node.coord] {
+                // superseded by a cheaper route found after it was queued
+                continue;
+            }
+            let g_source = search.g.get(&search.source).copied().unwrap_or(usize::MAX);
+            if node.cost >= g_source {
+                search.heap.push(node);
+                return true;
+            }
+            if self.exhausted(start, stats.expanded) {
+                search.heap.push(node);
+                return false;
+            }
+            stats.expanded += 1;
+            search.open.remove(&node.coord);
+            search.closed.insert(node.coord);
+            for n in env.neighbors(node.coord) {
+                if env.belief(n) == Belief::Occupied {
+                    continue;
+                }
+                let cost = node.incurred + env.distance(node.coord, n);
+                if search.g.get(&n).is_none_or(|&c| cost < c) {
+                    search.g.insert(n, cost);
+                    search.successor.insert(n, node.coord);
+                    if search.closed.contains(&n) {
+                        // revisited within an iteration only in the next one
+                        search.incons.insert(n);
+                    } else {
+                        search.open.insert(n);
+                        search.heap.push(HeurNode {
+                            cost: Self::key(env, search, n, cost),
+                            incurred: cost,
+                            coord: n,
+                        });
+                        stats.pushes += 1;
+                    }
+                }
+            }
+        }
+        true
+    }
+    fn exhausted(&self, start: Instant, expanded: usize) -> bool {
+        self.budget.expansions.is_some_and(|e| expanded >= e)
+            || self.budget.time.is_some_and(|t| start.elapsed() >= t)
+    }
+    /// Suboptimality the current solution is guaranteed to be within
+    fn bound(env: &S, search: &Search<S::Encoded>) -> f64 {
+        let g_source = search.g[&search.source];
+        // every cheaper route leaves the settled nodes through the open or inconsistent ones
+        let lower = search
+            .open
+            .iter()
+            .chain(&search.incons)
+            .map(|&n| search.g[&n].saturating_add(env.distance(n, search.source)))
+            .min()
+            .unwrap_or(usize::MAX);
+        let ratio = if lower >= g_source {
+            1.0
+        } else if lower == 0 {
+            f64::INFINITY
+        } else {
+            g_source as f64 / lower as f64
+        };
+        search.solved.map_or(ratio, |e| ratio.min(e))
+    }
+}
+
+impl<S: SpatialMap> Default for AraStarPlanner<S>
+where
+    S::Encoded: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: SpatialMap> Planner<S> for AraStarPlanner<S>
+where
+    S::Encoded: Eq + Hash,
+{
+    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Plan> {
+        if env.obstructed(target) {
+            return None;
+        };
+        let start = Instant::now();
+        let mut stats = SearchStats::default();
+        let (s_encode, t_encode) = (env.encode(source), env.encode(target));
+        if self.search.as_ref().is_none_or(|s| s.target != t_encode) {
+            self.initialize(env, s_encode, t_encode, &mut stats);
+        } else {
+            stats.provenance = Provenance::Repaired;
+        }
+        let mut search = self.search.take().unwrap();
+        if search.source != s_encode {
+            // costs to the target stay valid, a fresh iteration orders them for the new source
+            search.source = s_encode;
+            search.solved = None;
+            Self::restart(env, &mut search, &mut stats);
+        }
+        while self.improve_path(env, &mut search, start, &mut stats) {
+            search.solved = Some(search.epsilon);
+            if search.epsilon <= 1.0 || !search.g.contains_key(&s_encode) {
+                break;
+            }
+            // a decrement set on the field may not lower the inflation at all
+            let epsilon = (search.epsilon - self.decrement).max(1.0);
+            if epsilon >= search.epsilon {
+                break;
+            }
+            search.epsilon = epsilon;
+            Self::restart(env, &mut search, &mut stats);
+        }
+        stats.elapsed = start.elapsed();
+        let plan = search.g.contains_key(&s_encode).then(|| {
+            let mut plan = vec![];
+            let mut node = s_encode;
+            while node != t_encode {
+                node = search.successor[&node];
+                plan.push(if node == t_encode { target } else { env.decode(node) });
+            }
+            Plan::new(env, source, plan, stats).with_bound(Self::bound(env, &search))
+        });
+        self.search = Some(search);
+        plan
+    }
+    fn update(&mut self, _: &S, changes: &[CellChange]) {
+        if !changes.is_empty() {
+            self.search = None;
+        }
+    }
+}
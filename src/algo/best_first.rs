@@ -1,32 +1,37 @@
-use crate::global::types::plan::BestFirstPlan;
-use crate::global::types::plan::Planner;
-use crate::global::types::{ACoord, Belief, MinHeap, MinNode, SpatialMap};
+use crate::global::types::plan::{Plan, SearchStats};
+use crate::global::types::plan::{CellChange, Planner, VoxelPlan, VolumePlanner};
+use crate::global::types::{ACoord, Belief, MinHeap, MinNode, SpatialMap, VCoord, VolumetricMap};
+use crate::global::types::{SearchGraph, Surface, Volume};
 use std::collections::{HashMap, HashSet};
+use std::time::Instant;
 
 pub struct BestFirstPlanner;
 impl BestFirstPlanner {
-    fn encode_plan<S: SpatialMap>(
+    /// Precursors of the nodes reached up to the target, shared by flat and layered maps
+    fn encode_plan<G: SearchGraph>(
         &self,
-        env: &S,
-        source: S::Encoded,
-        target: S::Encoded,
-    ) -> Option<HashMap<S::Encoded, S::Encoded>>
-// where S::Encoded: std::fmt::Debug + Eq + std::hash::Hash + Copy
-    {
-        let mut p_queue: MinHeap<S::Encoded> = MinHeap::new();
-        let mut enqueue: HashSet<S::Encoded> = HashSet::new();
+        env: &G,
+        source: G::Node,
+        target: G::Node,
+        stats: &mut SearchStats,
+    ) -> Option<HashMap<G::Node, G::Node>> {
+        let mut p_queue: MinHeap<G::Node> = MinHeap::new();
+        let mut enqueue: HashSet<G::Node> = HashSet::new();
         let mut precursor = HashMap::new();
         p_queue.push(MinNode::new(0, source));
         enqueue.insert(source);
+        stats.pushes += 1;
         while let Some(node) = p_queue.pop() {
             if node.coord == target {
                 return Some(precursor);
             }
+            stats.expanded += 1;
             for n_xy in env.neighbors(node.coord) {
                 if enqueue.insert(n_xy) && env.belief(n_xy) != Belief::Occupied {
                     precursor.insert(n_xy, node.coord);
                     let cost = env.distance(n_xy, target);
                     p_queue.push(MinNode::new(cost, n_xy));
+                    stats.pushes += 1;
                 }
             }
         }
@@ -51,21 +56,44 @@ impl BestFirstPlanner {
 }
 
 impl<S: SpatialMap> Planner<S> for BestFirstPlanner {
-    type Plan = BestFirstPlan;
-    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Self::Plan> {
+    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Plan> {
         if env.obstructed(target) {
             return None;
         };
+        let start = Instant::now();
         let (s_encode, t_encode) = (env.encode(source), env.encode(target));
-
-        let precursor = self.encode_plan(env, s_encode, t_encode);
+        let mut stats = SearchStats::default();
+        let precursor = self.encode_plan(&Surface(env), s_encode, t_encode, &mut stats);
         match precursor {
             Some(map) => {
-                let plan = self.reconstruct_decode(env, &map, s_encode, t_encode);
-                Some(Self::Plan { plan })
+                let mut plan = self.reconstruct_decode(env, &map, s_encode, t_encode);
+                plan.reverse();
+                stats.elapsed = start.elapsed();
+                Some(Plan::new(env, source, plan, stats))
             }
             None => None,
         }
     }
-    fn update(&mut self, _: &S, _: ACoord, _: ACoord) {}
+    fn update(&mut self, _: &S, _: &[CellChange]) {}
+}
+
+impl<V: VolumetricMap> VolumePlanner<V> for BestFirstPlanner {
+    fn plan_volume(&mut self, env: &V, source: VCoord, target: VCoord) -> Option<VoxelPlan> {
+        if env.obstructed(target) {
+            return None;
+        };
+        let start = Instant::now();
+        let (s_encode, t_encode) = (env.encode(source), env.encode(target));
+        let mut stats = SearchStats::default();
+        let precursor = self.encode_plan(&Volume(env), s_encode, t_encode, &mut stats)?;
+        let mut plan = vec![];
+        let mut node = t_encode;
+        while node != s_encode {
+            plan.push(env.decode(node));
+            node = precursor[&node];
+        }
+        plan.reverse();
+        stats.elapsed = start.elapsed();
+        Some(VoxelPlan::new(env, source, plan, stats))
+    }
 }
@@ -0,0 +1,158 @@
+use crate::global::types::plan::{CellChange, Plan, Planner, SearchStats};
+use crate::global::types::{ACoord, Belief, HeurHeap, HeurNode, SpatialMap};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// A* from both ends at once, meeting in the middle
+///
+/// Each step expands the side with the smaller open list. Every route is a
+/// route through the open list of either side, so the best meeting found is
+/// optimal once the smallest estimate of one side reaches it. Movement costs
+/// are assumed symmetric.
+pub struct BidirectionalAStarPlanner;
+
+/// One direction of the search
+///
+/// # Attributes #
+/// * goal := node the heuristic leads to
+/// * incurred := node -> cost from the root of this side
+/// * parent := node -> previous node towards the root
+struct Frontier<E> {
+    goal: E,
+    queue: HeurHeap<E>,
+    incurred: HashMap<E, usize>,
+    parent: HashMap<E, E>,
+}
+
+impl<E: Copy + Eq + std::hash::Hash> Frontier<E> {
+    fn new<S: SpatialMap<Encoded = E>>(env: &S, root: E, goal: E) -> Self {
+        let mut frontier = Self {
+            goal,
+            queue: HeurHeap::new(),
+            incurred: HashMap::new(),
+            parent: HashMap::new(),
+        };
+        frontier.incurred.insert(root, 0);
+        frontier.queue.push(HeurNode {
+            incurred: 0,
+            cost: env.distance(root, goal),
+            coord: root,
+        });
+        frontier
+    }
+    /// Smallest estimate of the open list, stale entries dropped
+    fn top(&mut self) -> Option<usize> {
+        while let Some(node) = self.queue.peek() {
+            if node.incurred > self.incurred[&node.coord] {
+                self.queue.pop();
+                continue;
+            }
+            return Some(node.cost);
+        }
+        None
+    }
+    /// Expands the best node, recording routes which meet the other side
+    fn expand<S: SpatialMap<Encoded = E>>(
+        &mut self,
+        env: &S,
+        other: &Frontier<E>,
+        best: &mut Option<(usize, E)>,
+        stats: &mut SearchStats,
+    ) {
+        let Some(node) = self.queue.pop() else {
+            return;
+        };
+        stats.expanded += 1;
+        for n in env.neighbors(node.coord) {
+            if env.belief(n) == Belief::Occupied {
+                continue;
+            }
+            let cost = node.incurred + env.distance(node.coord, n);
+            if self.incurred.get(&n).is_none_or(|&c| cost < c) {
+                self.incurred.insert(n, cost);
+                self.parent.insert(n, node.coord);
+                self.queue.push(HeurNode {
+                    incurred: cost,
+                    cost: cost + env.distance(n, self.goal),
+                    coord: n,
+                });
+                stats.pushes += 1;
+                if let Some(&rest) = other.incurred.get(&n)
+                    && best.is_none_or(|(mu, _)| cost + rest < mu)
+                {
+                    *best = Some((cost + rest, n));
+                }
+            }
+        }
+    }
+    /// Nodes from the node back to the root, the root excluded
+    fn chain(&self, mut node: E) -> Vec<E> {
+        let mut nodes = vec![];
+        while let Some(&p) = self.parent.get(&node) {
+            nodes.push(node);
+            node = p;
+        }
+        nodes
+    }
+}
+
+impl BidirectionalAStarPlanner {
+    /// Node where the cheapest routes from both sides meet
+    fn meet<S: SpatialMap>(
+        forward: &mut Frontier<S::Encoded>,
+        backward: &mut Frontier<S::Encoded>,
+        env: &S,
+        stats: &mut SearchStats,
+    ) -> Option<S::Encoded> {
+        let mut best = None;
+        if forward.goal == backward.goal {
+            return Some(forward.goal);
+        }
+        loop {
+            let (Some(f_top), Some(b_top)) = (forward.top(), backward.top()) else {
+                // one side ran dry, no route leaves it beyond those already found
+                return best.map(|(_, n)| n);
+            };
+            if best.is_some_and(|(mu, _)| f_top.max(b_top) >= mu) {
+                return best.map(|(_, n)| n);
+            }
+            if forward.queue.len() <= backward.queue.len() {
+                forward.expand(env, backward, &mut best, stats);
+            } else {
+                backward.expand(env, forward, &mut best, stats);
+            }
+        }
+    }
+}
+
+impl<S: SpatialMap> Planner<S> for BidirectionalAStarPlanner {
+    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Plan> {
+        if env.obstructed(target) {
+            return None;
+        };
+        let start = Instant::now();
+        // each side starts with its root queued
+        let mut stats = SearchStats {
+            pushes: 2,
+            ..SearchStats::default()
+        };
+        let (s_encode, t_encode) = (env.encode(source), env.encode(target));
+        let mut forward = Frontier::new(env, s_encode, t_encode);
+        let mut backward = Frontier::new(env, t_encode, s_encode);
+        let meet = Self::meet(&mut forward, &mut backward, env, &mut stats)?;
+        let mut nodes = forward.chain(meet);
+        nodes.reverse();
+        nodes.extend(backward.chain(meet).into_iter().skip(1));
+        if meet != t_encode {
+            nodes.push(t_encode);
+        }
+        let mut plan: Vec<ACoord> = nodes.into_iter().map(|n| env.decode(n)).collect();
+        // the target node may cover more than the target cell
+        if let Some(last) = plan.last_mut() {
+            *last = target;
+        }
+        stats.elapsed = start.elapsed();
+        Some(Plan::new(env, source, plan, stats))
+    }
+    fn update(&mut self, _: &S, _: &[CellChange]) {}
+}
@@ -1,11 +1,10 @@
-#![allow(dead_code, unused_variables)]
-// use crate::global::types::LazyPQueue;
 use crate::global::types::IPQueue;
-use crate::global::types::plan::Planner;
-use crate::global::types::{ACoord, DStarPlan, SpatialMap, StarKey};
+use crate::global::types::plan::{CellChange, Plan, Planner, Provenance, SearchStats};
+use crate::global::types::{ACoord, Belief, SpatialMap, StarKey};
 use std::collections::HashMap;
 use std::hash::Hash;
 use std::mem;
+use std::time::Instant;
 
 /// Distance Map for DStarLite : ACoord -> (G, Rhs)
 ///
@@ -29,6 +28,7 @@ pub struct DStarPlanner<S: SpatialMap> {
     source: Option<S::Encoded>,
     target: Option<S::Encoded>,
     k: usize,
+    stats: SearchStats,
 }
 
 impl<S: SpatialMap> DStarPlanner<S>
@@ -42,6 +42,7 @@ where
             source: None,
             target: None,
             k: 0,
+            stats: SearchStats::default(),
         }
     }
     fn initialize(&mut self, env: &S, source: S::Encoded, target: S::Encoded) {
@@ -52,7 +53,6 @@ where
         self.target = Some(target);
         let h = env.distance(source, target);
         self.star.insert(target, (usize::MAX, 0));
-        self.star.insert(source, (usize::MAX, usize::MAX));
         self.pqueue.push(
             target,
             StarKey {
@@ -60,24 +60,54 @@ where
                 cost_dijkstra: 0,
             },
         );
+        self.stats.pushes += 1;
+    }
+    fn estimate(&self, u: S::Encoded) -> (G, Rhs) {
+        *self.star.get(&u).unwrap_or(&(usize::MAX, usize::MAX))
     }
     fn calculate_key(&self, env: &S, u: S::Encoded) -> StarKey {
         let source = self.source.unwrap();
-        let target = self.target.unwrap();
         let h = env.distance(u, source);
-        let &(g, rhs) = self.star.get(&u).unwrap_or(&(usize::MAX, usize::MAX));
+        let (g, rhs) = self.estimate(u);
         StarKey::new(g, rhs, h, self.k)
     }
     fn update_vertex(&mut self, env: &S, u: S::Encoded) {
-        let source = self.source.unwrap();
-        let (g, rhs) = self.star.get(&u).unwrap_or(&(usize::MAX, 0));
+        let (g, rhs) = self.estimate(u);
         if g != rhs {
             let ckey = self.calculate_key(env, u);
             self.pqueue.push(u, ckey);
+            self.stats.pushes += 1;
         } else {
             self.pqueue.remove(&u);
         }
     }
+    /// One step lookahead of the cost to go through the neighbours of s
+    fn lookahead(&self, env: &S, s: S::Encoded) -> Rhs {
+        if env.belief(s) == Belief::Occupied {
+            return usize::MAX;
+        }
+        let mut min_cost = usize::MAX;
+        for s_p in env.neighbors(s) {
+            let (g_sp, _) = self.estimate(s_p);
+            min_cost = min_cost.min(env.distance(s, s_p).saturating_add(g_sp));
+        }
+        min_cost
+    }
+    /// Recomputes the rhs of s from its neighbours and requeues it when inconsistent
+    fn revise(&mut self, env: &S, s: S::Encoded) {
+        if Some(s) == self.target {
+            return;
+        }
+        let rhs = self.lookahead(env, s);
+        let (g, _) = self.estimate(s);
+        if g == usize::MAX && rhs == usize::MAX {
+            // unreached nodes need no entry
+            self.star.remove(&s);
+        } else {
+            self.star.insert(s, (g, rhs));
+        }
+        self.update_vertex(env, s);
+    }
     fn propagate_cost_rhs(&mut self, env: &S, u: S::Encoded) {
         let target = self.target.unwrap();
         let (g_u, _) = self.star[&u];
@@ -86,94 +116,55 @@ where
                 continue;
             }
             let rhs_new = env.distance(s, u).saturating_add(g_u);
-            let (g, rhs) = self.star.entry(s).or_insert((usize::MAX, usize::MAX));
-            let rhs_updated = (*rhs).min(rhs_new);
-            if rhs_new < *rhs {
-                *rhs = rhs_new;
+            let (g, rhs) = self.estimate(s);
+            if rhs_new < rhs {
+                self.star.insert(s, (g, rhs_new));
             }
             self.update_vertex(env, s);
         }
     }
-    fn find_min_neighbor_g(&self, env: &S, s: S::Encoded) -> usize {
-        let target = self.target.unwrap();
-        let mut min_cost = usize::MAX;
-        for s_p in env.neighbors(s) {
-            if let Some(&(g_sp, _)) = self.star.get(&s_p) {
-                min_cost = min_cost.min(env.distance(s, s_p).saturating_add(g_sp));
-            }
-        }
-        min_cost
-    }
     fn propagate_cost_g(&mut self, env: &S, u: S::Encoded, g_old: usize) {
-        let target = self.target.unwrap();
-        if u == target {
-            return;
-        }
         for s in env.neighbors(u) {
-            // only update if not equal
-            
-            let &(g_s, rhs) = self.star.get(&s).unwrap_or(&(usize::MAX, usize::MAX));
-            if rhs != env.distance(u, s).saturating_add(g_old)  { continue; } 
-            let rhs_new = self.find_min_neighbor_g(env, s);
-            self.star.insert(s, (g_s, rhs_new));
-            self.update_vertex(env, s);
+            // only neighbours whose estimate went through u are affected
+            let (_, rhs) = self.estimate(s);
+            if rhs == env.distance(u, s).saturating_add(g_old) {
+                self.revise(env, s);
+            }
         }
     }
-    // fn propagate_cost_g(&mut self, env: &S, u: S::Encoded, g_old: usize) {
-    //     let target = self.target.unwrap();
-    //     if u == target {
-    //         return;
-    //     }
-    //     for s in env.neighbors(u) {
-    //         // only update if not equal
-    //         let g = if let Some(&(g_s, rhs)) = self.star.get(&s) {
-    //             if rhs != env.distance(u, s).saturating_add(g_old) {
-    //                 continue;
-    //             }
-    //             g_s
-    //         } else {
-    //             continue;
-    //         };
-    //         let rhs_new = self.find_min_neighbor_g(env, s);
-    //         self.star.insert(s, (g, rhs_new));
-    //         self.update_vertex(env, s);
-    //     }
-    // }
     fn compute_shortest_path(&mut self, env: &S) {
         let source = self.source.unwrap();
-        let target = self.target.unwrap();
-        loop {
-            let (g, rhs) = self.star[&source];
-            if let Some((top_coord, top_key)) = self.pqueue.peek() {
-                let start_key = self.calculate_key(env, source);
-                if g == rhs && top_key <= start_key {
-                    break;
-                }
+        while let Some((_, top_key)) = self.pqueue.peek() {
+            let (g, rhs) = self.estimate(source);
+            // reversed due to starkey reversed compare for order for minheap
+            if g == rhs && top_key <= self.calculate_key(env, source) {
+                break;
             }
             let (u_coord, k_old) = self.pqueue.pop().unwrap();
-            let &(g_u, rhs_u) = match self.star.get(&u_coord) {
-                Some(entry) => entry,
-                None => continue,
-            };
+            let (g_u, rhs_u) = self.estimate(u_coord);
             let k_new = self.calculate_key(env, u_coord);
-            // reversed due to starkey reversed compare for order for minheap
             if k_old > k_new {
                 self.pqueue.push(u_coord, k_new);
-            } else if g_u > rhs_u {
+                self.stats.pushes += 1;
+                continue;
+            }
+            self.stats.expanded += 1;
+            if g_u > rhs_u {
                 self.star.insert(u_coord, (rhs_u, rhs_u));
-                self.pqueue.remove(&u_coord);
                 self.propagate_cost_rhs(env, u_coord);
             } else {
-                let g_old = g_u;
                 self.star.insert(u_coord, (usize::MAX, rhs_u));
-                self.propagate_cost_g(env, u_coord, g_old);
-                self.update_vertex(env, u_coord);
+                self.propagate_cost_g(env, u_coord, g_u);
+                self.revise(env, u_coord);
             }
         }
     }
     fn reconstruct_decode(&mut self, env: &S) -> Option<Vec<ACoord>> {
         let source = self.source.unwrap();
         let target = self.target.unwrap();
+        if self.estimate(source).0 == usize::MAX {
+            return None;
+        }
         let mut plan = Vec::new();
         let mut node_curr = Some(source);
         let mut node_next;
@@ -185,15 +176,18 @@ where
             if target == current {
                 return Some(plan);
             }
+            if plan.len() > self.star.len() {
+                // descending estimates which are not yet settled can circle
+                return None;
+            }
             node_next = None;
             best_cost = usize::MAX;
             for neigh in env.neighbors(current) {
-                if let Some(&(g_n, rhs)) = self.star.get(&neigh) {
-                    let cost = g_n.saturating_add(env.distance(current, neigh));
-                    if cost < best_cost {
-                        best_cost = cost;
-                        node_next = Some(neigh);
-                    }
+                let (g_n, _) = self.estimate(neigh);
+                let cost = g_n.saturating_add(env.distance(current, neigh));
+                if cost < best_cost {
+                    best_cost = cost;
+                    node_next = Some(neigh);
                 }
             }
             mem::swap(&mut node_curr, &mut node_next);
@@ -215,60 +209,82 @@ where
     }
     fn revise_plan(&mut self, env: &S) {
         let source = self.source.unwrap();
-        // let (g, rhs) = self.star[&source];
-        let (g, rhs) = self.star[&source];
         self.update_vertex(env, source);
         self.compute_shortest_path(env);
     }
 }
 
+impl<S: SpatialMap> Default for DStarPlanner<S>
+where
+    S::Encoded: Eq + Hash + std::fmt::Debug + Eq,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl<S: SpatialMap> Planner<S> for DStarPlanner<S>
 where
     S::Encoded: Eq + Hash + std::fmt::Debug + Eq,
 {
-    type Plan = DStarPlan;
-    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Self::Plan> {
-        if self.source.is_none() || self.target.is_none() {
+    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Plan> {
+        if env.obstructed(target) {
+            return None;
+        };
+        let start = Instant::now();
+        let s_encode = env.encode(source);
+        let t_encode = env.encode(target);
+        if self.source.is_none() || self.target != Some(t_encode) {
+            self.stats = SearchStats::default();
             self.new_plan(env, source, target);
         } else {
-            let s_encode = env.encode(source);
-            let t_encode = env.encode(target);
-
+            // the pushes of updates since the last query count towards the repair
+            self.stats.provenance = Provenance::Repaired;
+            // the heuristic shrinks as the source moves, k keeps old keys a lower bound
             self.k += env.distance(self.source.unwrap(), s_encode);
             self.source = Some(s_encode);
             self.revise_plan(env);
         }
-        match self.reconstruct_decode(env) {
-            Some(mut plan) => {
-                plan.push(target);
-                Some(Self::Plan { plan })
-            },
-            None => None,
+        let mut stats = mem::take(&mut self.stats);
+        let mut plan = self.reconstruct_decode(env)?;
+        // the target node may cover more than the target cell
+        if let Some(last) = plan.last_mut() {
+            *last = target;
         }
+        stats.elapsed = start.elapsed();
+        Some(Plan::new(env, source, plan, stats))
     }
-    fn update(&mut self, env: &S, position: ACoord, obstacle: ACoord) {
-        // TODO: Need to integrate rhs for when we find better paths
+    fn update(&mut self, env: &S, changes: &[CellChange]) {
         if self.source.is_none() || self.target.is_none() {
             return;
         }
-        let node = env.encode(obstacle);
-        let leaf = env.leaf(obstacle);
-        let source = self.source.unwrap();
         let target = self.target.unwrap();
-        // if worse
-        let &(g_obs, rhs_obs) = self
+        if env.obstructed(env.decode(target)) || env.encode(env.decode(target)) != target {
+            // the target is walled in or no longer a node, the next plan starts over
+            self.target = None;
+            return;
+        }
+        // edge costs change around every changed cell, whether it got better or worse
+        let mut changed: Vec<S::Encoded> = changes.iter().map(|c| env.encode(c.coord)).collect();
+        // nodes merged or split by the change carry estimates of regions which no longer exist
+        let stale: Vec<S::Encoded> = self
             .star
-            .get(&node)
-            .unwrap_or(&(usize::MAX, usize::MAX));
-        self.star.insert(node, (usize::MAX, usize::MAX));
-        self.star.insert(leaf, (usize::MAX, usize::MAX));
-        self.propagate_cost_g(env, leaf, g_obs);
-        self.update_vertex(env, leaf);
-        self.propagate_cost_g(env, node, g_obs);
-        self.update_vertex(env, node);
-        // // if better
-        // self.star.insert(u_coord, (rhs_u, rhs_u));
-        // self.pqueue.remove(&u_coord);
-        // self.propagate_cost_rhs(env, u_coord);
+            .keys()
+            .filter(|&&n| env.encode(env.decode(n)) != n)
+            .copied()
+            .collect();
+        for n in stale {
+            self.star.remove(&n);
+            self.pqueue.remove(&n);
+            // the nodes now covering it are new, their neighbours have new edges
+            changed.extend(env.covering(n));
+        }
+        for node in changed {
+            let mut affected = env.neighbors(node);
+            affected.push(node);
+            for s in affected {
+                self.revise(env, s);
+            }
+        }
     }
 }
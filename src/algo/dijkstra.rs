@@ -0,0 +1,140 @@
+use crate::global::types::plan::{CellChange, Plan, Planner, SearchStats};
+use crate::global::types::{ACoord, Belief, MinHeap, MinNode, SpatialMap};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Cost-to-go of every node settled from a target
+///
+/// # Attributes #
+/// * target := node the costs lead to
+/// * costs := node -> cost of the cheapest path to the target
+/// * successor := node -> next node along that path
+#[derive(Clone, Debug)]
+pub struct CostField<E> {
+    pub target: E,
+    pub costs: HashMap<E, usize>,
+    pub successor: HashMap<E, E>,
+}
+
+impl<E: Copy + Eq + std::hash::Hash> CostField<E> {
+    pub fn cost(&self, node: E) -> Option<usize> {
+        self.costs.get(&node).copied()
+    }
+    /// Nodes following the field down to the target, the node itself excluded
+    pub fn descend(&self, mut node: E) -> Option<Vec<E>> {
+        if !self.costs.contains_key(&node) {
+            return None;
+        }
+        let mut path = Vec::new();
+        while node != self.target {
+            node = self.successor[&node];
+            path.push(node);
+        }
+        Some(path)
+    }
+    /// Cost at the decoded coordinate of every settled node, for flow fields and heatmaps
+    pub fn decode<S: SpatialMap<Encoded = E>>(&self, env: &S) -> HashMap<ACoord, usize> {
+        self.costs.iter().map(|(&n, &c)| (env.decode(n), c)).collect()
+    }
+}
+
+/// Uniform cost search outward from the target
+///
+/// # Attributes #
+/// * radius := costs beyond the radius are left unsettled
+/// * early_exit := plans stop once the source is settled
+pub struct DijkstraPlanner {
+    pub radius: Option<usize>,
+    pub early_exit: bool,
+}
+
+impl DijkstraPlanner {
+    pub fn new() -> Self {
+        Self {
+            radius: None,
+            early_exit: true,
+        }
+    }
+    pub fn with_radius(mut self, radius: usize) -> Self {
+        self.radius = Some(radius);
+        self
+    }
+    pub fn with_early_exit(mut self, early_exit: bool) -> Self {
+        self.early_exit = early_exit;
+        self
+    }
+    /// Cost-to-go of every node within the radius of the target
+    pub fn field<S: SpatialMap>(&self, env: &S, target: ACoord) -> CostField<S::Encoded> {
+        self.settle(env, env.encode(target), None, &mut SearchStats::default())
+    }
+    fn settle<S: SpatialMap>(
+        &self,
+        env: &S,
+        target: S::Encoded,
+        stop: Option<S::Encoded>,
+        stats: &mut SearchStats,
+    ) -> CostField<S::Encoded> {
+        let mut p_queue: MinHeap<S::Encoded> = MinHeap::new();
+        let mut field = CostField {
+            target,
+            costs: HashMap::new(),
+            successor: HashMap::new(),
+        };
+        field.costs.insert(target, 0);
+        p_queue.push(MinNode::new(0, target));
+        stats.pushes += 1;
+        while let Some(node) = p_queue.pop() {
+            if node.cost > field.costs[&node.coord] {
+                continue;
+            }
+            if Some(node.coord) == stop {
+                break;
+            }
+            stats.expanded += 1;
+            // movement costs are symmetric so searching from the target yields cost-to-go
+            for n in env.neighbors(node.coord) {
+                if env.belief(n) == Belief::Occupied {
+                    continue;
+                }
+                let cost = node.cost + env.distance(node.coord, n);
+                if self.radius.is_some_and(|r| cost > r) {
+                    continue;
+                }
+                if field.costs.get(&n).is_none_or(|&c| cost < c) {
+                    field.costs.insert(n, cost);
+                    field.successor.insert(n, node.coord);
+                    p_queue.push(MinNode::new(cost, n));
+                    stats.pushes += 1;
+                }
+            }
+        }
+        field
+    }
+}
+
+impl Default for DijkstraPlanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: SpatialMap> Planner<S> for DijkstraPlanner {
+    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Plan> {
+        if env.obstructed(target) {
+            return None;
+        };
+        let start = Instant::now();
+        let mut stats = SearchStats::default();
+        let (s_encode, t_encode) = (env.encode(source), env.encode(target));
+        let stop = if self.early_exit { Some(s_encode) } else { None };
+        let field = self.settle(env, t_encode, stop, &mut stats);
+        let plan = field
+            .descend(s_encode)?
+            .into_iter()
+            .map(|n| env.decode(n))
+            .collect();
+        stats.elapsed = start.elapsed();
+        Some(Plan::new(env, source, plan, stats))
+    }
+    fn update(&mut self, _: &S, _: &[CellChange]) {}
+}
@@ -0,0 +1,360 @@
+use crate::environment::quad::QuadTree;
+use crate::global::types::plan::{CellChange, Plan, Planner, SearchStats};
+use crate::global::types::{
+    ACoord, Belief, Bounds, HCoord, HeurHeap, HeurNode, MinHeap, MinNode, SpatialMap,
+};
+use crate::hierarchy::encoding::{extent, transform};
+use crate::hierarchy::proximity::edge_leaves;
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// Abstract view of one cluster
+///
+/// # Attributes #
+/// * nodes := free leaves of the cluster bordering another cluster
+/// * crossings := node -> leaves across the border it can step onto
+/// * intra := node -> other nodes reachable inside the cluster and their cost
+#[derive(Clone, Debug, Default)]
+struct Cluster {
+    nodes: Vec<HCoord>,
+    crossings: HashMap<HCoord, Vec<HCoord>>,
+    intra: HashMap<HCoord, Vec<(HCoord, usize)>>,
+}
+
+/// Hierarchical A* over the quadtree
+///
+/// Clusters are the nodes at the cluster level, leaves coarser than the level
+/// form their own cluster. Routes are searched over the entrances between
+/// clusters first and refined at the leaf level inside each visited cluster.
+///
+/// # Attributes #
+/// * level := quadtree level of the clusters
+/// * clusters := cluster -> entrances and intra-cluster costs
+/// * dirty := regions changed since the abstract graph was last patched
+/// * seen := tree id and journal generation the abstract graph accounts for, none until built
+pub struct HpaStarPlanner {
+    level: usize,
+    clusters: HashMap<HCoord, Cluster>,
+    dirty: Vec<Bounds>,
+    seen: Option<(u64, usize)>,
+}
+
+impl HpaStarPlanner {
+    pub fn new(level: usize) -> Self {
+        Self {
+            level,
+            clusters: HashMap::new(),
+            dirty: Vec::new(),
+            seen: None,
+        }
+    }
+    /// Marks a region whose beliefs changed, the graph is patched on the next plan
+    ///
+    /// Changes made through the tree are picked up from its journal, only
+    /// information edited directly needs marking.
+    pub fn invalidate(&mut self, rect: Bounds) {
+        self.dirty.push(rect);
+    }
+    /// Number of entrance nodes across every cluster
+    pub fn entrances(&self) -> usize {
+        self.clusters.values().map(|c| c.nodes.len()).sum()
+    }
+    fn cluster_of(&self, leaf: HCoord) -> HCoord {
+        if leaf.l >= self.level {
+            leaf
+        } else {
+            transform(&leaf, self.level)
+        }
+    }
+    fn build(&mut self, env: &QuadTree) {
+        self.clusters.clear();
+        self.dirty.clear();
+        let clusters: HashSet<HCoord> = env.leaves().map(|l| self.cluster_of(l.node)).collect();
+        for cluster in clusters {
+            self.rebuild(env, cluster);
+        }
+    }
+    /// Rebuilds every cluster overlapping a dirty region along with the ring around them
+    fn patch(&mut self, env: &QuadTree) {
+        let dirty = std::mem::take(&mut self.dirty);
+        let mut affected = HashSet::new();
+        for rect in dirty {
+            // beliefs next to the region decide whether its cells are entrances
+            let grown = Bounds {
+                min_x: rect.min_x - 1,
+                min_y: rect.min_y - 1,
+                max_x: rect.max_x + 1,
+                max_y: rect.max_y + 1,
+            };
+            self.clusters.retain(|c, _| !extent(c).intersects(&grown));
+            for leaf in env.range(&grown) {
+                affected.insert(self.cluster_of(leaf.node));
+            }
+        }
+        let mut ring = HashSet::new();
+        for &cluster in &affected {
+            for leaf in env.range(&extent(&cluster)) {
+                // crossings into the cluster may name leaves which no longer exist
+                let adjacent = edge_leaves(env, leaf.node);
+                for n in adjacent.into_iter().chain(env.neighbors(leaf.node)) {
+                    let other = self.cluster_of(n);
+                    if !affected.contains(&other) {
+                        ring.insert(other);
+                    }
+                }
+            }
+        }
+        for cluster in affected.into_iter().chain(ring) {
+            self.rebuild(env, cluster);
+        }
+    }
+    fn rebuild(&mut self, env: &QuadTree, cluster: HCoord) {
+        let mut abstracted = Cluster::default();
+        for leaf in env.range(&extent(&cluster)) {
+            if self.cluster_of(leaf.node) != cluster {
+                continue;
+            }
+            let across: Vec<HCoord> = env
+                .neighbors(leaf.node)
+                .into_iter()
+                .filter(|&n| self.cluster_of(n) != cluster)
+                .collect();
+            if !across.is_empty() && leaf.belief != Belief::Occupied {
+                abstracted.nodes.push(leaf.node);
+                abstracted.crossings.insert(leaf.node, across);
+            }
+        }
+        for &node in &abstracted.nodes {
+            let costs = self.explore(env, cluster, node);
+            let reachable = abstracted
+                .nodes
+                .iter()
+                .filter(|&&n| n != node)
+                .filter_map(|n| costs.get(n).map(|&(cost, _)| (*n, cost)))
+                .collect();
+            abstracted.intra.insert(node, reachable);
+        }
+        if abstracted.nodes.is_empty() {
+            self.clusters.remove(&cluster);
+        } else {
+            self.clusters.insert(cluster, abstracted);
+        }
+    }
+    /// Dijkstra from the source restricted to the leaves of the cluster, node -> (cost, precursor)
+    fn explore(
+        &self,
+        env: &QuadTree,
+        cluster: HCoord,
+        source: HCoord,
+    ) -> HashMap<HCoord, (usize, HCoord)> {
+        let mut p_queue: MinHeap<HCoord> = MinHeap::new();
+        let mut costs = HashMap::new();
+        costs.insert(source, (0, source));
+        p_queue.push(MinNode::new(0, source));
+        while let Some(node) = p_queue.pop() {
+            if node.cost > costs[&node.coord].0 {
+                continue;
+            }
+            for n in env.neighbors(node.coord) {
+                if self.cluster_of(n) != cluster {
+                    continue;
+                }
+                let cost = node.cost + env.distance(node.coord, n);
+                if costs.get(&n).is_none_or(|&(c, _)| cost < c) {
+                    costs.insert(n, (cost, node.coord));
+                    p_queue.push(MinNode::new(cost, n));
+                }
+            }
+        }
+        costs
+    }
+    /// Leaves from a to b inside their shared cluster, a excluded
+    fn refine(
+        &self,
+        env: &QuadTree,
+        a: HCoord,
+        b: HCoord,
+        stats: &mut SearchStats,
+    ) -> Option<Vec<HCoord>> {
+        let cluster = self.cluster_of(a);
+        let mut p_queue: HeurHeap<HCoord> = HeurHeap::new();
+        let mut precursor = HashMap::new();
+        let mut incurred = HashMap::new();
+        incurred.insert(a, 0);
+        p_queue.push(HeurNode {
+            incurred: 0,
+            cost: env.distance(a, b),
+            coord: a,
+        });
+        stats.pushes += 1;
+        while let Some(node) = p_queue.pop() {
+            if node.coord == b {
+                let mut path = vec![b];
+                let mut curr = b;
+                while let Some(&prev) = precursor.get(&curr) {
+                    if prev == a {
+                        break;
+                    }
+                    path.push(prev);
+                    curr = prev;
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if node.incurred > incurred[&node.coord] {
+                continue;
+            }
+            stats.expanded += 1;
+            for n in env.neighbors(node.coord) {
+                if self.cluster_of(n) != cluster {
+                    continue;
+                }
+                let cost = node.incurred + env.distance(node.coord, n);
+                if incurred.get(&n).is_none_or(|&c| cost < c) {
+                    incurred.insert(n, cost);
+                    precursor.insert(n, node.coord);
+                    p_queue.push(HeurNode {
+                        incurred: cost,
+                        cost: cost + env.distance(n, b),
+                        coord: n,
+                    });
+                    stats.pushes += 1;
+                }
+            }
+        }
+        None
+    }
+    /// Abstract route from source to target through the cluster entrances
+    fn route(
+        &self,
+        env: &QuadTree,
+        source: HCoord,
+        target: HCoord,
+        stats: &mut SearchStats,
+    ) -> Option<Vec<HCoord>> {
+        // source and target join the abstract graph through their own clusters
+        let (s_cluster, t_cluster) = (self.cluster_of(source), self.cluster_of(target));
+        let s_costs = self.explore(env, s_cluster, source);
+        let t_costs = self.explore(env, t_cluster, target);
+        let empty = Cluster::default();
+        let s_nodes = &self.clusters.get(&s_cluster).unwrap_or(&empty).nodes;
+        let t_nodes = &self.clusters.get(&t_cluster).unwrap_or(&empty).nodes;
+        let edges = |node: HCoord| -> Vec<(HCoord, usize)> {
+            let mut edges = Vec::new();
+            if node == source {
+                for n in s_nodes {
+                    if let Some(&(cost, _)) = s_costs.get(n) {
+                        edges.push((*n, cost));
+                    }
+                }
+                if let Some(&(cost, _)) = s_costs.get(&target) {
+                    edges.push((target, cost));
+                }
+            }
+            if let Some(cluster) = self.clusters.get(&self.cluster_of(node)) {
+                edges.extend(cluster.intra.get(&node).into_iter().flatten().copied());
+                for &n in cluster.crossings.get(&node).into_iter().flatten() {
+                    edges.push((n, env.distance(node, n)));
+                }
+            }
+            if t_nodes.contains(&node)
+                && let Some(&(cost, _)) = t_costs.get(&node)
+            {
+                edges.push((target, cost));
+            }
+            edges
+        };
+        let mut p_queue: HeurHeap<HCoord> = HeurHeap::new();
+        let mut precursor = HashMap::new();
+        let mut incurred = HashMap::new();
+        incurred.insert(source, 0);
+        p_queue.push(HeurNode {
+            incurred: 0,
+            cost: env.distance(source, target),
+            coord: source,
+        });
+        stats.pushes += 1;
+        while let Some(node) = p_queue.pop() {
+            if node.coord == target {
+                let mut route = vec![target];
+                let mut curr = target;
+                while let Some(&prev) = precursor.get(&curr) {
+                    route.push(prev);
+                    curr = prev;
+                }
+                route.reverse();
+                return Some(route);
+            }
+            if node.incurred > incurred[&node.coord] {
+                continue;
+            }
+            stats.expanded += 1;
+            for (n, step) in edges(node.coord) {
+                let cost = node.incurred + step;
+                if incurred.get(&n).is_none_or(|&c| cost < c) {
+                    incurred.insert(n, cost);
+                    precursor.insert(n, node.coord);
+                    p_queue.push(HeurNode {
+                        incurred: cost,
+                        cost: cost + env.distance(n, target),
+                        coord: n,
+                    });
+                    stats.pushes += 1;
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Planner<QuadTree> for HpaStarPlanner {
+    fn plan(&mut self, env: &QuadTree, source: ACoord, target: ACoord) -> Option<Plan> {
+        if env.obstructed(target) {
+            return None;
+        };
+        let start = Instant::now();
+        let mut stats = SearchStats::default();
+        // another tree, or edits dropped from the journal, leave nothing to patch from
+        let edits = self
+            .seen
+            .filter(|&(id, _)| id == env.id())
+            .and_then(|(_, generation)| env.journal_since(generation));
+        match edits {
+            Some(edits) => {
+                self.dirty.extend_from_slice(edits);
+                if !self.dirty.is_empty() {
+                    self.patch(env);
+                }
+            }
+            None => self.build(env),
+        }
+        self.seen = Some((env.id(), env.generation()));
+        let (s_encode, t_encode) = (env.encode(source), env.encode(target));
+        let route = self.route(env, s_encode, t_encode, &mut stats)?;
+        let mut plan = Vec::new();
+        for pair in route.windows(2) {
+            let (a, b) = (pair[0], pair[1]);
+            if self.cluster_of(a) == self.cluster_of(b) {
+                // only the corridor between consecutive entrances is searched at leaf level
+                for n in self.refine(env, a, b, &mut stats)? {
+                    plan.push(env.decode(n));
+                }
+            } else {
+                plan.push(env.decode(b));
+            }
+        }
+        stats.elapsed = start.elapsed();
+        Some(Plan::new(env, source, plan, stats))
+    }
+    fn update(&mut self, _: &QuadTree, changes: &[CellChange]) {
+        for change in changes {
+            let coord = change.coord;
+            self.invalidate(Bounds {
+                min_x: coord.x,
+                min_y: coord.y,
+                max_x: coord.x,
+                max_y: coord.y,
+            });
+        }
+    }
+}
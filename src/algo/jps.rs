@@ -0,0 +1,225 @@
+use crate::environment::grid::Grid;
+use crate::global::types::plan::{CellChange, Plan, Planner, SearchStats};
+use crate::global::types::{ACoord, Belief, Bounds, Connectivity, HeurHeap, HeurNode, SpatialMap};
+use std::collections::HashMap;
+use std::time::Instant;
+
+/// Jump Point Search over uniform cost grids
+///
+/// Straight runs and diagonals are skipped until a cell with a forced
+/// neighbour, only those jump points enter the open list. Diagonal moves
+/// follow the grid and never cut a corner, so a diagonal needs both of its
+/// orthogonal cells open. Unrecorded cells are free, jumps are confined to
+/// the grid bounds grown by one cell around the source and target.
+pub struct JpsPlanner;
+
+/// Cells a jump may visit
+struct Arena<'a> {
+    env: &'a Grid,
+    region: Bounds,
+    target: ACoord,
+}
+
+impl Arena<'_> {
+    fn walkable(&self, x: isize, y: isize) -> bool {
+        let coord = ACoord { x, y };
+        self.region.contains(coord) && self.env.belief(coord) != Belief::Occupied
+    }
+    fn diagonal(&self) -> bool {
+        self.env.connectivity != Connectivity::Four
+    }
+    /// Next jump point from the node along the direction
+    fn jump(&self, mut node: ACoord, d: ACoord) -> Option<ACoord> {
+        loop {
+            let (x, y) = (node.x, node.y);
+            if !self.walkable(x, y) {
+                return None;
+            }
+            if node == self.target {
+                return Some(node);
+            }
+            if d.x != 0 && d.y != 0 {
+                if self.jump(ACoord { x: x + d.x, y }, ACoord { x: d.x, y: 0 }).is_some()
+                    || self.jump(ACoord { x, y: y + d.y }, ACoord { x: 0, y: d.y }).is_some()
+                {
+                    return Some(node);
+                }
+                // the diagonal continues only past two open cells
+                if !(self.walkable(x + d.x, y) && self.walkable(x, y + d.y)) {
+                    return None;
+                }
+            } else if d.x != 0 {
+                if (self.walkable(x, y - 1) && !self.walkable(x - d.x, y - 1))
+                    || (self.walkable(x, y + 1) && !self.walkable(x - d.x, y + 1))
+                {
+                    return Some(node);
+                }
+            } else {
+                if (self.walkable(x - 1, y) && !self.walkable(x - 1, y - d.y))
+                    || (self.walkable(x + 1, y) && !self.walkable(x + 1, y - d.y))
+                {
+                    return Some(node);
+                }
+                // without diagonals horizontal runs branch off every vertical step
+                if !self.diagonal()
+                    && (self.jump(ACoord { x: x + 1, y }, ACoord { x: 1, y: 0 }).is_some()
+                        || self.jump(ACoord { x: x - 1, y }, ACoord { x: -1, y: 0 }).is_some())
+                {
+                    return Some(node);
+                }
+            }
+            node = ACoord { x: x + d.x, y: y + d.y };
+        }
+    }
+    /// Directions worth following from a jump point reached along d
+    fn pruned(&self, node: ACoord, d: Option<ACoord>) -> Vec<ACoord> {
+        let (x, y) = (node.x, node.y);
+        let Some(d) = d else {
+            // the source looks in every direction the grid allows
+            let occupied = |c: ACoord| !self.walkable(c.x, c.y);
+            return self
+                .env
+                .connectivity
+                .directions()
+                .iter()
+                .copied()
+                .filter(|&d| !occupied(ACoord { x: x + d.x, y: y + d.y }))
+                .filter(|&d| !Connectivity::cuts_corner(node, d, occupied))
+                .collect();
+        };
+        let mut directions = Vec::new();
+        if d.x != 0 && d.y != 0 {
+            let (open_x, open_y) = (self.walkable(x + d.x, y), self.walkable(x, y + d.y));
+            if open_x {
+                directions.push(ACoord { x: d.x, y: 0 });
+            }
+            if open_y {
+                directions.push(ACoord { x: 0, y: d.y });
+            }
+            if open_x && open_y {
+                directions.push(d);
+            }
+            return directions;
+        }
+        // sideways cells of a straight run, n := normal to the run
+        let n = ACoord { x: d.y.abs(), y: d.x.abs() };
+        let ahead = self.walkable(x + d.x, y + d.y);
+        if ahead {
+            directions.push(d);
+        }
+        for side in [1, -1] {
+            let s = ACoord { x: n.x * side, y: n.y * side };
+            if !self.walkable(x + s.x, y + s.y) {
+                continue;
+            }
+            directions.push(s);
+            if self.diagonal() && ahead {
+                directions.push(ACoord { x: d.x + s.x, y: d.y + s.y });
+            }
+        }
+        directions
+    }
+}
+
+impl JpsPlanner {
+    /// Cost of a straight or diagonal run, the sum of its steps
+    fn run_cost(env: &Grid, a: ACoord, b: ACoord) -> usize {
+        let steps = a.x.abs_diff(b.x).max(a.y.abs_diff(b.y));
+        let d = ACoord {
+            x: (b.x - a.x).signum(),
+            y: (b.y - a.y).signum(),
+        };
+        steps * env.distance(ACoord { x: 0, y: 0 }, d)
+    }
+    /// Jump points from source to target and their precursors
+    fn search(
+        arena: &Arena,
+        source: ACoord,
+        stats: &mut SearchStats,
+    ) -> Option<HashMap<ACoord, ACoord>> {
+        let (env, target) = (arena.env, arena.target);
+        let mut p_queue: HeurHeap<ACoord> = HeurHeap::new();
+        let mut incurred = HashMap::new();
+        let mut precursor: HashMap<ACoord, ACoord> = HashMap::new();
+        incurred.insert(source, 0);
+        p_queue.push(HeurNode {
+            incurred: 0,
+            cost: env.distance(source, target),
+            coord: source,
+        });
+        stats.pushes += 1;
+        while let Some(node) = p_queue.pop() {
+            if node.coord == target {
+                return Some(precursor);
+            }
+            if node.incurred > incurred[&node.coord] {
+                continue;
+            }
+            stats.expanded += 1;
+            let d = precursor.get(&node.coord).map(|p| ACoord {
+                x: (node.coord.x - p.x).signum(),
+                y: (node.coord.y - p.y).signum(),
+            });
+            for dir in arena.pruned(node.coord, d) {
+                let start = ACoord {
+                    x: node.coord.x + dir.x,
+                    y: node.coord.y + dir.y,
+                };
+                let Some(jump) = arena.jump(start, dir) else {
+                    continue;
+                };
+                let cost = node.incurred + Self::run_cost(env, node.coord, jump);
+                if incurred.get(&jump).is_none_or(|&c| cost < c) {
+                    incurred.insert(jump, cost);
+                    precursor.insert(jump, node.coord);
+                    p_queue.push(HeurNode {
+                        incurred: cost,
+                        cost: cost + env.distance(jump, target),
+                        coord: jump,
+                    });
+                    stats.pushes += 1;
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Planner<Grid> for JpsPlanner {
+    fn plan(&mut self, env: &Grid, source: ACoord, target: ACoord) -> Option<Plan> {
+        if env.obstructed(target) {
+            return None;
+        };
+        let start = Instant::now();
+        let mut stats = SearchStats::default();
+        let region = Bounds {
+            min_x: env.bounds.min_x.min(source.x).min(target.x) - 1,
+            min_y: env.bounds.min_y.min(source.y).min(target.y) - 1,
+            max_x: env.bounds.max_x.max(source.x).max(target.x) + 1,
+            max_y: env.bounds.max_y.max(source.y).max(target.y) + 1,
+        };
+        let arena = Arena { env, region, target };
+        let precursor = Self::search(&arena, source, &mut stats)?;
+        // runs between jump points are expanded back into single steps
+        let mut plan = Vec::new();
+        let mut node = target;
+        while node != source {
+            let prev = precursor[&node];
+            let d = ACoord {
+                x: (prev.x - node.x).signum(),
+                y: (prev.y - node.y).signum(),
+            };
+            while node != prev {
+                plan.push(node);
+                node = ACoord {
+                    x: node.x + d.x,
+                    y: node.y + d.y,
+                };
+            }
+        }
+        plan.reverse();
+        stats.elapsed = start.elapsed();
+        Some(Plan::new(env, source, plan, stats))
+    }
+    fn update(&mut self, _: &Grid, _: &[CellChange]) {}
+}
@@ -0,0 +1,200 @@
+use crate::global::types::IPQueue;
+use crate::global::types::plan::{CellChange, Plan, Planner, Provenance, SearchStats};
+use crate::global::types::{ACoord, Belief, SpatialMap, StarKey};
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::mem;
+use std::time::Instant;
+
+/// Cost from the source : node -> (G, Rhs)
+type Star<T> = HashMap<T, (usize, usize)>;
+
+/// Lifelong Planning A* between a fixed source and target
+///
+/// Costs are searched forward from the source and kept between queries,
+/// map updates only reopen the nodes whose lookahead changed. A new
+/// source or target starts the search over, D* Lite covers a moving source.
+pub struct LpaStarPlanner<S: SpatialMap> {
+    star: Star<S::Encoded>,
+    pqueue: IPQueue<StarKey, S::Encoded>,
+    source: Option<S::Encoded>,
+    target: Option<S::Encoded>,
+    stats: SearchStats,
+}
+
+impl<S: SpatialMap> LpaStarPlanner<S>
+where
+    S::Encoded: Eq + Hash,
+{
+    pub fn new() -> Self {
+        Self {
+            star: Star::new(),
+            pqueue: IPQueue::new(),
+            source: None,
+            target: None,
+            stats: SearchStats::default(),
+        }
+    }
+    fn initialize(&mut self, env: &S, source: S::Encoded, target: S::Encoded) {
+        self.star.clear();
+        self.pqueue.clear();
+        self.source = Some(source);
+        self.target = Some(target);
+        self.star.insert(source, (usize::MAX, 0));
+        self.pqueue.push(source, self.calculate_key(env, source));
+        self.stats.pushes += 1;
+    }
+    fn estimate(&self, u: S::Encoded) -> (usize, usize) {
+        *self.star.get(&u).unwrap_or(&(usize::MAX, usize::MAX))
+    }
+    fn calculate_key(&self, env: &S, u: S::Encoded) -> StarKey {
+        let (g, rhs) = self.estimate(u);
+        StarKey::new(g, rhs, env.distance(u, self.target.unwrap()), 0)
+    }
+    /// Cheapest arrival at s through its expanded neighbours
+    fn lookahead(&self, env: &S, s: S::Encoded) -> usize {
+        if env.belief(s) == Belief::Occupied {
+            return usize::MAX;
+        }
+        env.neighbors(s)
+            .into_iter()
+            .map(|p| self.estimate(p).0.saturating_add(env.distance(p, s)))
+            .min()
+            .unwrap_or(usize::MAX)
+    }
+    fn update_vertex(&mut self, env: &S, u: S::Encoded) {
+        let (g, mut rhs) = self.estimate(u);
+        if Some(u) != self.source {
+            rhs = self.lookahead(env, u);
+        }
+        if g == usize::MAX && rhs == usize::MAX {
+            self.star.remove(&u);
+        } else {
+            self.star.insert(u, (g, rhs));
+        }
+        if g != rhs {
+            self.pqueue.push(u, self.calculate_key(env, u));
+            self.stats.pushes += 1;
+        } else {
+            self.pqueue.remove(&u);
+        }
+    }
+    fn compute_shortest_path(&mut self, env: &S) {
+        let target = self.target.unwrap();
+        while let Some((_, top_key)) = self.pqueue.peek() {
+            let (g, rhs) = self.estimate(target);
+            // reversed due to starkey reversed compare for order for minheap
+            if g == rhs && top_key <= self.calculate_key(env, target) {
+                break;
+            }
+            let (u, _) = self.pqueue.pop().unwrap();
+            self.stats.expanded += 1;
+            let (g_u, rhs_u) = self.estimate(u);
+            if g_u > rhs_u {
+                self.star.insert(u, (rhs_u, rhs_u));
+            } else {
+                // underconsistent, the node is reopened along with everything it reached
+                self.star.insert(u, (usize::MAX, rhs_u));
+                self.update_vertex(env, u);
+            }
+            for s in env.neighbors(u) {
+                self.update_vertex(env, s);
+            }
+        }
+    }
+    /// Walks the costs back from the target, source excluded
+    fn reconstruct(&self, env: &S) -> Option<Vec<S::Encoded>> {
+        let (source, target) = (self.source.unwrap(), self.target.unwrap());
+        if self.estimate(target).0 == usize::MAX {
+            return None;
+        }
+        let mut path = vec![];
+        let mut node = target;
+        while node != source {
+            path.push(node);
+            node = env
+                .neighbors(node)
+                .into_iter()
+                .min_by_key(|&p| self.estimate(p).0.saturating_add(env.distance(p, node)))?;
+            if path.len() > self.star.len() {
+                return None;
+            }
+        }
+        path.reverse();
+        Some(path)
+    }
+}
+
+impl<S: SpatialMap> Default for LpaStarPlanner<S>
+where
+    S::Encoded: Eq + Hash,
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: SpatialMap> Planner<S> for LpaStarPlanner<S>
+where
+    S::Encoded: Eq + Hash,
+{
+    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Plan> {
+        if env.obstructed(target) {
+            return None;
+        };
+        let start = Instant::now();
+        let (s_encode, t_encode) = (env.encode(source), env.encode(target));
+        if self.source != Some(s_encode) || self.target != Some(t_encode) {
+            self.stats = SearchStats::default();
+            self.initialize(env, s_encode, t_encode);
+        } else {
+            // the pushes of updates since the last query count towards the repair
+            self.stats.provenance = Provenance::Repaired;
+        }
+        self.compute_shortest_path(env);
+        let mut stats = mem::take(&mut self.stats);
+        let mut plan: Vec<ACoord> = self
+            .reconstruct(env)?
+            .into_iter()
+            .map(|n| env.decode(n))
+            .collect();
+        // the target node may cover more than the target cell
+        if let Some(last) = plan.last_mut() {
+            *last = target;
+        }
+        stats.elapsed = start.elapsed();
+        Some(Plan::new(env, source, plan, stats))
+    }
+    fn update(&mut self, env: &S, changes: &[CellChange]) {
+        if self.source.is_none() || self.target.is_none() {
+            return;
+        }
+        let source = self.source.unwrap();
+        if env.encode(env.decode(source)) != source {
+            // the source is no longer a node, the next plan starts over
+            self.source = None;
+            return;
+        }
+        let mut changed: Vec<S::Encoded> = changes.iter().map(|c| env.encode(c.coord)).collect();
+        // nodes merged or split by the change carry estimates of regions which no longer exist
+        let stale: Vec<S::Encoded> = self
+            .star
+            .keys()
+            .filter(|&&n| env.encode(env.decode(n)) != n)
+            .copied()
+            .collect();
+        for n in stale {
+            self.star.remove(&n);
+            self.pqueue.remove(&n);
+            // the nodes now covering it are new, their neighbours have new edges
+            changed.extend(env.covering(n));
+        }
+        for node in changed {
+            let mut affected = env.neighbors(node);
+            affected.push(node);
+            for s in affected {
+                self.update_vertex(env, s);
+            }
+        }
+    }
+}
@@ -1,3 +1,13 @@
 pub mod a_star;
+pub mod ara_star;
 pub mod best_first;
+pub mod bidirectional_a_star;
 pub mod d_star;
+pub mod dijkstra;
+pub mod hpa_star;
+pub mod jps;
+pub mod lpa_star;
+pub mod rrt_star;
+pub mod skeleton;
+pub mod smoothing;
+pub mod theta_star;
@@ -0,0 +1,345 @@
+use crate::algo::smoothing::string_pull;
+use crate::global::line::Traversal;
+use crate::global::rng::Rng;
+use crate::global::types::plan::{CellChange, Plan, Planner, SearchStats};
+use crate::global::types::{ACoord, Opacity, SpatialMap};
+use std::f64::consts::PI;
+use std::time::Instant;
+
+/// Continuous position in map space, cell centres at integer coordinates
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Point {
+    x: f64,
+    y: f64,
+}
+
+impl Point {
+    fn from_cell(coord: ACoord) -> Self {
+        Self {
+            x: coord.x as f64,
+            y: coord.y as f64,
+        }
+    }
+    fn distance(&self, other: &Point) -> f64 {
+        (self.x - other.x).hypot(self.y - other.y)
+    }
+    /// Cells the segment to other passes through, in order from this one
+    fn crossed(&self, other: &Point) -> Vec<ACoord> {
+        // corners are rounded by direction, walking from the lower end agrees both ways
+        if (other.x, other.y) < (self.x, self.y) {
+            let mut cells = other.crossed(self);
+            cells.reverse();
+            return cells;
+        }
+        // traversed cells span from their lower corner, points sit at cell centres
+        let direction = (other.x - self.x, other.y - self.y);
+        Traversal::new((self.x + 0.5, self.y + 0.5), direction)
+            .take_while(|&(_, t)| t <= 1.0)
+            .map(|(cell, _)| cell)
+            .collect()
+    }
+    /// Point at most step along the way to other
+    fn steer(&self, other: &Point, step: f64) -> Point {
+        let d = self.distance(other);
+        if d <= step {
+            return *other;
+        }
+        Point {
+            x: self.x + (other.x - self.x) * step / d,
+            y: self.y + (other.y - self.y) * step / d,
+        }
+    }
+}
+
+/// Vertex of the tree grown from the source
+struct Vertex {
+    point: Point,
+    parent: usize,
+    cost: f64,
+    children: Vec<usize>,
+}
+
+/// Rapidly exploring random tree, optimal with rewiring (RRT*)
+///
+/// Positions are sampled in continuous map space within the cells the map
+/// holds beliefs for, an edge is kept when every cell the segment between its
+/// endpoints passes through is see-through. The neighbourhood searched for
+/// cheaper parents shrinks as the tree grows, as asymptotic optimality needs.
+/// The plan is the cells the branch passes through with redundant corners
+/// removed, so consecutive waypoints are in line of sight.
+///
+/// # Attributes #
+/// * seed := seed of the sampler, equal seeds give equal plans
+/// * iterations := samples drawn per plan
+/// * step := longest edge added towards a sample
+/// * goal_bias := chance of sampling the target instead
+/// * gamma := scale of the neighbourhood radius gamma (log n / n)^(1/2), at most
+///   a step, none derives it from the sampled area
+/// * rewire := whether the tree is rewired, plain RRT stops at the first plan
+/// * unknown := whether unknown cells block an edge
+pub struct RrtStarPlanner {
+    pub seed: u64,
+    pub iterations: usize,
+    pub step: f64,
+    pub goal_bias: f64,
+    pub gamma: Option<f64>,
+    pub rewire: bool,
+    pub unknown: Opacity,
+}
+
+impl RrtStarPlanner {
+    pub fn new() -> Self {
+        Self {
+            seed: 0,
+            iterations: 3000,
+            step: 2.0,
+            goal_bias: 0.05,
+            gamma: None,
+            rewire: true,
+            unknown: Opacity::Transparent,
+        }
+    }
+    /// Plain RRT, the first branch reaching the target is the plan
+    pub fn rrt() -> Self {
+        Self {
+            rewire: false,
+            ..Self::new()
+        }
+    }
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+    pub fn with_iterations(mut self, iterations: usize) -> Self {
+        self.iterations = iterations;
+        self
+    }
+    pub fn with_step(mut self, step: f64) -> Self {
+        self.step = step;
+        self
+    }
+    pub fn with_goal_bias(mut self, goal_bias: f64) -> Self {
+        self.goal_bias = goal_bias;
+        self
+    }
+    pub fn with_gamma(mut self, gamma: f64) -> Self {
+        self.gamma = Some(gamma);
+        self
+    }
+    pub fn with_unknown(mut self, unknown: Opacity) -> Self {
+        self.unknown = unknown;
+        self
+    }
+    /// Sampling area, the known cells grown by a step around source and target
+    fn area<S: SpatialMap>(&self, env: &S, source: ACoord, target: ACoord) -> (Point, Point) {
+        let (mut low, mut high) = (Point::from_cell(source), Point::from_cell(source));
+        let corners = env
+            .regions()
+            .into_iter()
+            .flat_map(|r| {
+                [
+                    ACoord { x: r.bounds.min_x, y: r.bounds.min_y },
+                    ACoord { x: r.bounds.max_x, y: r.bounds.max_y },
+                ]
+            })
+            .chain([target]);
+        for c in corners {
+            low = Point {
+                x: low.x.min(c.x as f64),
+                y: low.y.min(c.y as f64),
+            };
+            high = Point {
+                x: high.x.max(c.x as f64),
+                y: high.y.max(c.y as f64),
+            };
+        }
+        let margin = self.step.max(1.0);
+        (
+            Point {
+                x: low.x - margin,
+                y: low.y - margin,
+            },
+            Point {
+                x: high.x + margin,
+                y: high.y + margin,
+            },
+        )
+    }
+    /// Neighbourhood radius among n vertices, above the bound which keeps RRT* optimal
+    fn radius(&self, low: &Point, high: &Point, n: usize) -> f64 {
+        let area = (high.x - low.x) * (high.y - low.y);
+        let gamma = self.gamma.unwrap_or_else(|| 2.0 * 1.5f64.sqrt() * (area / PI).sqrt());
+        let n = n as f64;
+        (gamma * (n.ln() / n).sqrt()).min(self.step)
+    }
+    /// Whether the continuous segment a -> b only passes see-through cells
+    ///
+    /// Crossing a corner diagonally also checks the two cells beside it, as
+    /// the supercover of a line of sight does.
+    fn visible<S: SpatialMap>(&self, env: &S, a: &Point, b: &Point) -> bool {
+        let blocks = |c: ACoord| env.belief(env.encode(c)).blocks(self.unknown);
+        let mut prev: Option<ACoord> = None;
+        for cell in a.crossed(b) {
+            if blocks(cell) {
+                return false;
+            }
+            if let Some(p) = prev
+                && p.x != cell.x
+                && p.y != cell.y
+                && (blocks(ACoord { x: cell.x, y: p.y }) || blocks(ACoord { x: p.x, y: cell.y }))
+            {
+                return false;
+            }
+            prev = Some(cell);
+        }
+        true
+    }
+    /// Tree vertices from the source to the target, the target last
+    ///
+    /// Every sample drawn counts as an expansion, every vertex added as a push.
+    fn grow<S: SpatialMap>(
+        &self,
+        env: &S,
+        source: ACoord,
+        target: ACoord,
+        stats: &mut SearchStats,
+    ) -> Option<Vec<Point>> {
+        let (low, high) = self.area(env, source, target);
+        let goal = Point::from_cell(target);
+        let mut rng = Rng::new(self.seed);
+        let mut tree = vec![Vertex {
+            point: Point::from_cell(source),
+            parent: 0,
+            cost: 0.0,
+            children: vec![],
+        }];
+        // vertices which see the target within a step
+        let mut reaching = vec![];
+        if self.visible(env, &tree[0].point, &goal) && tree[0].point.distance(&goal) <= self.step {
+            reaching.push(0);
+        }
+        for _ in 0..self.iterations {
+            if !self.rewire && !reaching.is_empty() {
+                break;
+            }
+            stats.expanded += 1;
+            let sample = if rng.next_f64() < self.goal_bias {
+                goal
+            } else {
+                Point {
+                    x: rng.range(low.x, high.x),
+                    y: rng.range(low.y, high.y),
+                }
+            };
+            let nearest = (0..tree.len())
+                .min_by(|&a, &b| {
+                    let da = tree[a].point.distance(&sample);
+                    da.total_cmp(&tree[b].point.distance(&sample))
+                })
+                .unwrap();
+            let point = tree[nearest].point.steer(&sample, self.step);
+            if !self.visible(env, &tree[nearest].point, &point) {
+                continue;
+            }
+            let near: Vec<usize> = if self.rewire {
+                let radius = self.radius(&low, &high, tree.len());
+                let within = |v: usize| tree[v].point.distance(&point) <= radius;
+                let seen = |v: usize| self.visible(env, &tree[v].point, &point);
+                (0..tree.len())
+                    .filter(|&v| v == nearest || (within(v) && seen(v)))
+                    .collect()
+            } else {
+                vec![nearest]
+            };
+            // the cheapest visible neighbour becomes the parent
+            let (parent, cost) = near
+                .iter()
+                .map(|&v| (v, tree[v].cost + tree[v].point.distance(&point)))
+                .min_by(|a, b| a.1.total_cmp(&b.1))
+                .unwrap();
+            let index = tree.len();
+            tree.push(Vertex {
+                point,
+                parent,
+                cost,
+                children: vec![],
+            });
+            tree[parent].children.push(index);
+            stats.pushes += 1;
+            for &v in near.iter().filter(|&&v| v != parent && v != 0) {
+                let through = cost + point.distance(&tree[v].point);
+                if through < tree[v].cost {
+                    Self::reparent(&mut tree, v, index, through);
+                }
+            }
+            if point.distance(&goal) <= self.step && self.visible(env, &point, &goal) {
+                reaching.push(index);
+            }
+        }
+        let best = reaching
+            .into_iter()
+            .min_by(|&a, &b| {
+                let ca = tree[a].cost + tree[a].point.distance(&goal);
+                let cb = tree[b].cost + tree[b].point.distance(&goal);
+                ca.total_cmp(&cb)
+            })?;
+        let mut points = vec![goal];
+        let mut v = best;
+        while v != 0 {
+            points.push(tree[v].point);
+            v = tree[v].parent;
+        }
+        points.push(tree[0].point);
+        points.reverse();
+        Some(points)
+    }
+    /// Moves a vertex under a new parent, its subtree follows the cost change
+    fn reparent(tree: &mut [Vertex], v: usize, parent: usize, cost: f64) {
+        let old = tree[v].parent;
+        tree[old].children.retain(|&c| c != v);
+        tree[parent].children.push(v);
+        tree[v].parent = parent;
+        let delta = tree[v].cost - cost;
+        let mut stack = vec![v];
+        while let Some(u) = stack.pop() {
+            tree[u].cost -= delta;
+            stack.extend(tree[u].children.iter().copied());
+        }
+    }
+}
+
+impl Default for RrtStarPlanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: SpatialMap> Planner<S> for RrtStarPlanner {
+    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Plan> {
+        if env.obstructed(target) {
+            return None;
+        };
+        let clock = Instant::now();
+        let mut stats = SearchStats::default();
+        if source == target {
+            return Some(Plan::segments(env, source, vec![], stats));
+        }
+        let points = self.grow(env, source, target, &mut stats)?;
+        // every cell the branch passes through, so consecutive cells see each other
+        let mut crossed: Vec<ACoord> = vec![];
+        for (a, b) in points.iter().zip(&points[1..]) {
+            for c in a.crossed(b) {
+                if crossed.last() != Some(&c) {
+                    crossed.push(c);
+                }
+            }
+        }
+        // the walk starts where the branch last leaves the source cell
+        let start = crossed.iter().rposition(|&c| c == source).map_or(0, |i| i + 1);
+        let cells = &crossed[start..];
+        let plan = string_pull(env, source, cells, self.unknown);
+        stats.elapsed = clock.elapsed();
+        Some(Plan::segments(env, source, plan, stats))
+    }
+    fn update(&mut self, _: &S, _: &[CellChange]) {}
+}
@@ -0,0 +1,255 @@
+use crate::global::types::{ACoord, Bounds, CARDINALS, COMPASS_ROSE, SpatialMap};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Obstacle cells further apart than this are considered distinct sites
+const SEPARATION: usize = 2;
+
+/// Brushfire estimate for a free cell
+///
+/// # Attributes #
+/// * distance := cardinal steps to the closest obstacle
+/// * label := connected obstacle component which reached the cell first
+/// * origin := obstacle cell which reached the cell first
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+struct Front {
+    distance: usize,
+    label: usize,
+    origin: ACoord,
+}
+
+impl Front {
+    /// Neighbouring fronts grown from distinct obstacles meet on the skeleton
+    fn collides(&self, other: &Front) -> bool {
+        let separation =
+            self.origin.x.abs_diff(other.origin.x) + self.origin.y.abs_diff(other.origin.y);
+        self.label != other.label || separation > SEPARATION
+    }
+}
+
+/// Chain of skeleton cells between two skeleton nodes
+///
+/// # Attributes #
+/// * source, target := skeleton nodes, junctions or endpoints
+/// * path := cells from source to target inclusive
+/// * length := summed map distance along the path
+/// * clearance := smallest obstacle distance along the path
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct SkeletonEdge {
+    pub source: ACoord,
+    pub target: ACoord,
+    pub path: Vec<ACoord>,
+    pub length: usize,
+    pub clearance: usize,
+}
+
+/// Generalised Voronoi skeleton of the free space
+///
+/// Cells equidistant to two distinct obstacles form the skeleton, junctions
+/// and endpoints become nodes and the chains between them edges.
+///
+/// # Attributes #
+/// * cells := skeleton cell -> clearance
+/// * nodes := junctions and endpoints of the skeleton
+/// * members := junction cell -> node representing its cluster
+/// * edges := chains between nodes carrying their clearance
+#[derive(Clone, Debug)]
+pub struct Skeleton {
+    pub cells: HashMap<ACoord, usize>,
+    pub nodes: HashSet<ACoord>,
+    pub members: HashMap<ACoord, ACoord>,
+    pub edges: Vec<SkeletonEdge>,
+}
+
+impl Skeleton {
+    /// Extracts the skeleton of the free space inside the inclusive bounds
+    pub fn extract<S: SpatialMap>(env: &S, bounds: &Bounds) -> Self {
+        let fronts = brushfire(env, bounds);
+        let mut cells = HashMap::new();
+        for (&coord, front) in &fronts {
+            for d in CARDINALS {
+                let n_xy = ACoord {
+                    x: coord.x + d.x,
+                    y: coord.y + d.y,
+                };
+                if let Some(n_front) = fronts.get(&n_xy)
+                    && front.collides(n_front)
+                    && front.distance >= n_front.distance
+                {
+                    cells.insert(coord, front.distance);
+                    break;
+                }
+            }
+        }
+        let mut skeleton = Self {
+            cells,
+            nodes: HashSet::new(),
+            members: HashMap::new(),
+            edges: Vec::new(),
+        };
+        skeleton.connect(env);
+        skeleton
+    }
+    /// Skeleton cells adjacent across the compass rose
+    pub fn adjacent(&self, coord: ACoord) -> Vec<ACoord> {
+        let mut adjacent = Vec::new();
+        for d in COMPASS_ROSE {
+            let n_xy = ACoord {
+                x: coord.x + d.x,
+                y: coord.y + d.y,
+            };
+            if self.cells.contains_key(&n_xy) {
+                adjacent.push(n_xy);
+            }
+        }
+        adjacent
+    }
+    /// Edges incident to a skeleton node
+    pub fn incident(&self, node: ACoord) -> impl Iterator<Item = &SkeletonEdge> {
+        self.edges
+            .iter()
+            .filter(move |e| e.source == node || e.target == node)
+    }
+    /// Node representing the junction cluster of a cell
+    pub fn node(&self, coord: ACoord) -> Option<ACoord> {
+        self.members.get(&coord).copied()
+    }
+    fn cluster(&mut self, junctions: &HashSet<ACoord>) {
+        // adjacent junction cells collapse onto a single node
+        for &coord in junctions {
+            if self.members.contains_key(&coord) {
+                continue;
+            }
+            self.nodes.insert(coord);
+            self.members.insert(coord, coord);
+            let mut stack = vec![coord];
+            while let Some(c) = stack.pop() {
+                for n_xy in self.adjacent(c) {
+                    if junctions.contains(&n_xy) && !self.members.contains_key(&n_xy) {
+                        self.members.insert(n_xy, coord);
+                        stack.push(n_xy);
+                    }
+                }
+            }
+        }
+    }
+    fn connect<S: SpatialMap>(&mut self, env: &S) {
+        let junctions: HashSet<ACoord> = self
+            .cells
+            .keys()
+            .filter(|&&c| self.adjacent(c).len() != 2)
+            .copied()
+            .collect();
+        self.cluster(&junctions);
+        let mut visited: HashSet<(ACoord, ACoord)> = HashSet::new();
+        let mut pending: Vec<ACoord> = self.members.keys().copied().collect();
+        let mut remaining: HashSet<ACoord> = self
+            .cells
+            .keys()
+            .filter(|c| !self.members.contains_key(c))
+            .copied()
+            .collect();
+        loop {
+            while let Some(cell) = pending.pop() {
+                let node = self.members[&cell];
+                for step in self.adjacent(cell) {
+                    if self.node(step) == Some(node) || !visited.insert((cell, step)) {
+                        continue;
+                    }
+                    let edge = self.trace(env, cell, step);
+                    remaining.retain(|c| !edge.path.contains(c));
+                    let n = edge.path.len();
+                    visited.insert((edge.path[n - 1], edge.path[n - 2]));
+                    self.edges.push(edge);
+                }
+            }
+            // loops without junctions need an arbitrary node to be traced
+            match remaining.iter().next() {
+                Some(&coord) => {
+                    remaining.remove(&coord);
+                    self.nodes.insert(coord);
+                    self.members.insert(coord, coord);
+                    pending.push(coord);
+                }
+                None => break,
+            }
+        }
+    }
+    fn trace<S: SpatialMap>(&self, env: &S, cell: ACoord, step: ACoord) -> SkeletonEdge {
+        let mut path = vec![cell, step];
+        let mut clearance = self.cells[&cell].min(self.cells[&step]);
+        let mut length = env.distance(env.leaf(cell), env.leaf(step));
+        let (mut prev, mut curr) = (cell, step);
+        while !self.members.contains_key(&curr) {
+            let next = self.adjacent(curr).into_iter().find(|&n| n != prev);
+            match next {
+                Some(next) => {
+                    clearance = clearance.min(self.cells[&next]);
+                    length += env.distance(env.leaf(curr), env.leaf(next));
+                    path.push(next);
+                    (prev, curr) = (curr, next);
+                }
+                None => break,
+            }
+        }
+        SkeletonEdge {
+            source: self.members[&cell],
+            target: self.members.get(&curr).copied().unwrap_or(curr),
+            path,
+            length,
+            clearance,
+        }
+    }
+}
+
+/// Labels connected obstacles and grows them into the free space
+fn brushfire<S: SpatialMap>(env: &S, bounds: &Bounds) -> HashMap<ACoord, Front> {
+    let mut labels: HashMap<ACoord, usize> = HashMap::new();
+    let mut queue = VecDeque::new();
+    let mut label = 0;
+    for x in bounds.min_x..=bounds.max_x {
+        for y in bounds.min_y..=bounds.max_y {
+            let coord = ACoord { x, y };
+            if !env.obstructed(coord) || labels.contains_key(&coord) {
+                continue;
+            }
+            // flood the obstacle component, gaps between components lie on the skeleton
+            let mut stack = vec![coord];
+            labels.insert(coord, label);
+            while let Some(o_xy) = stack.pop() {
+                queue.push_back((o_xy, 0, label, o_xy));
+                for d in COMPASS_ROSE {
+                    let n_xy = ACoord {
+                        x: o_xy.x + d.x,
+                        y: o_xy.y + d.y,
+                    };
+                    if bounds.contains(n_xy) && !labels.contains_key(&n_xy) && env.obstructed(n_xy)
+                    {
+                        labels.insert(n_xy, label);
+                        stack.push(n_xy);
+                    }
+                }
+            }
+            label += 1;
+        }
+    }
+    let mut fronts = HashMap::new();
+    while let Some((coord, distance, label, origin)) = queue.pop_front() {
+        for d in CARDINALS {
+            let n_xy = ACoord {
+                x: coord.x + d.x,
+                y: coord.y + d.y,
+            };
+            if !bounds.contains(n_xy) || labels.contains_key(&n_xy) || fronts.contains_key(&n_xy) {
+                continue;
+            }
+            let front = Front {
+                distance: distance + 1,
+                label,
+                origin,
+            };
+            fronts.insert(n_xy, front);
+            queue.push_back((n_xy, front.distance, label, origin));
+        }
+    }
+    fronts
+}
@@ -0,0 +1,219 @@
+use crate::global::line::supercover;
+use crate::global::types::plan::{CellChange, Plan, Planner};
+use crate::global::types::{ACoord, Opacity, PlanIter, SpatialMap};
+use std::time::Instant;
+
+/// Drops waypoints the last kept waypoint sees past
+///
+/// Walks the nodes from the source keeping a waypoint only when the previous
+/// kept waypoint cannot see the one after it, consecutive waypoints of the
+/// result see each other wherever the input ones did. The last node is kept.
+pub fn string_pull<S: SpatialMap>(
+    env: &S,
+    source: ACoord,
+    nodes: &[ACoord],
+    unknown: Opacity,
+) -> Vec<ACoord> {
+    let mut pulled = vec![];
+    let mut anchor = source;
+    for (i, &node) in nodes.iter().enumerate() {
+        let Some(&after) = nodes.get(i + 1) else {
+            pulled.push(node);
+            break;
+        };
+        if !env.line_of_sight(anchor, after, unknown) {
+            pulled.push(node);
+            anchor = node;
+        }
+    }
+    pulled
+}
+
+/// Merges runs of equal straight or diagonal moves into one segment
+///
+/// Only axis aligned and diagonal runs are merged, the straight walk along
+/// the merged segment then steps through exactly the same cells.
+pub fn merge_collinear(source: ACoord, nodes: &[ACoord]) -> Vec<ACoord> {
+    let direction = |a: ACoord, b: ACoord| {
+        let (dx, dy) = (b.x - a.x, b.y - a.y);
+        // slanted moves have no exact unit step
+        if dx != 0 && dy != 0 && dx.abs() != dy.abs() {
+            return None;
+        }
+        Some(ACoord {
+            x: dx.signum(),
+            y: dy.signum(),
+        })
+    };
+    let mut merged: Vec<ACoord> = vec![];
+    let mut prev = source;
+    for &node in nodes {
+        if node == prev {
+            continue;
+        }
+        if let Some(&last) = merged.last() {
+            let before = merged.len().checked_sub(2).map_or(source, |i| merged[i]);
+            let d = direction(before, last);
+            if d.is_some() && d == direction(last, node) {
+                merged.pop();
+            }
+        }
+        merged.push(node);
+        prev = node;
+    }
+    merged
+}
+
+/// Post-processing of the cell by cell plans of every planner
+///
+/// Collinear moves are merged and, when pulling, corners the previous corner
+/// sees past are dropped. The optional spline replaces each segment with a
+/// Catmull-Rom curve through the waypoints when every cell the curve passes
+/// keeps the clearance from blocking cells, the straight segment stays otherwise.
+///
+/// # Attributes #
+/// * pull := whether corners are dropped by string pulling
+/// * unknown := whether unknown cells block sight and clearance
+/// * samples := cells sampled along each curved segment, none without a spline
+/// * clearance := cells kept free around every curve sample
+pub struct Smoother {
+    pub pull: bool,
+    pub unknown: Opacity,
+    pub samples: usize,
+    pub clearance: usize,
+}
+
+impl Smoother {
+    pub fn new() -> Self {
+        Self {
+            pull: true,
+            unknown: Opacity::Transparent,
+            samples: 0,
+            clearance: 0,
+        }
+    }
+    pub fn with_pull(mut self, pull: bool) -> Self {
+        self.pull = pull;
+        self
+    }
+    pub fn with_unknown(mut self, unknown: Opacity) -> Self {
+        self.unknown = unknown;
+        self
+    }
+    pub fn with_spline(mut self, samples: usize, clearance: usize) -> Self {
+        self.samples = samples;
+        self.clearance = clearance;
+        self
+    }
+    /// Waypoints of the plan walked from the source, simplified
+    ///
+    /// The search statistics carry over with the smoothing time added, a
+    /// suboptimality bound does not as the plan is costed by segment length.
+    pub fn smooth<S: SpatialMap>(&self, env: &S, source: ACoord, plan: &Plan) -> Plan {
+        let start = Instant::now();
+        let nodes: Vec<ACoord> = plan.iter().copied().collect();
+        let mut waypoints = merge_collinear(source, &nodes);
+        if self.pull {
+            waypoints = string_pull(env, source, &waypoints, self.unknown);
+        }
+        if self.samples > 0 {
+            waypoints = self.spline(env, source, &waypoints);
+        }
+        let mut stats = plan.stats;
+        stats.elapsed += start.elapsed();
+        Plan::segments(env, source, waypoints, stats)
+    }
+    fn clear<S: SpatialMap>(&self, env: &S, coord: ACoord) -> bool {
+        let r = self.clearance as isize;
+        (-r..=r).all(|dx| {
+            (-r..=r).all(|dy| {
+                let c = ACoord {
+                    x: coord.x + dx,
+                    y: coord.y + dy,
+                };
+                !env.belief(env.encode(c)).blocks(self.unknown)
+            })
+        })
+    }
+    /// Curves each segment through its neighbouring waypoints where the map allows
+    fn spline<S: SpatialMap>(&self, env: &S, source: ACoord, waypoints: &[ACoord]) -> Vec<ACoord> {
+        let mut points = vec![source];
+        points.extend_from_slice(waypoints);
+        let point = |i: isize| points[i.clamp(0, points.len() as isize - 1) as usize];
+        let mut curved = vec![];
+        for i in 0..points.len() as isize - 1 {
+            let (p0, p1, p2, p3) = (point(i - 1), point(i), point(i + 1), point(i + 2));
+            let mut cells: Vec<ACoord> = vec![];
+            for k in 1..self.samples {
+                let c = catmull_rom(p0, p1, p2, p3, k as f64 / self.samples as f64);
+                if c != p1 && c != p2 && cells.last() != Some(&c) {
+                    cells.push(c);
+                }
+            }
+            cells.push(p2);
+            let mut prev = p1;
+            let fits = cells.iter().all(|&c| {
+                // the waypoints may sit closer to a wall than the curve between them
+                let leg = supercover(prev, c);
+                let ok = env.line_of_sight(prev, c, self.unknown)
+                    && leg.into_iter().all(|l| l == p1 || l == p2 || self.clear(env, l));
+                prev = c;
+                ok
+            });
+            if fits {
+                curved.extend(cells);
+            } else {
+                curved.push(p2);
+            }
+        }
+        curved
+    }
+}
+
+impl Default for Smoother {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Uniform Catmull-Rom curve between p1 and p2 at t, rounded to a cell
+fn catmull_rom(p0: ACoord, p1: ACoord, p2: ACoord, p3: ACoord, t: f64) -> ACoord {
+    let (t2, t3) = (t * t, t * t * t);
+    let axis = |a: isize, b: isize, c: isize, d: isize| {
+        let (a, b, c, d) = (a as f64, b as f64, c as f64, d as f64);
+        0.5 * (2.0 * b
+            + (c - a) * t
+            + (2.0 * a - 5.0 * b + 4.0 * c - d) * t2
+            + (3.0 * b - a - 3.0 * c + d) * t3)
+    };
+    ACoord {
+        x: axis(p0.x, p1.x, p2.x, p3.x).round() as isize,
+        y: axis(p0.y, p1.y, p2.y, p3.y).round() as isize,
+    }
+}
+
+/// Any planner whose plans are smoothed before they are followed
+///
+/// # Attributes #
+/// * planner := planner producing the raw plans
+/// * smoother := post-processing applied to every plan
+pub struct SmoothedPlanner<P> {
+    pub planner: P,
+    pub smoother: Smoother,
+}
+
+impl<P> SmoothedPlanner<P> {
+    pub fn new(planner: P, smoother: Smoother) -> Self {
+        Self { planner, smoother }
+    }
+}
+
+impl<S: SpatialMap, P: Planner<S>> Planner<S> for SmoothedPlanner<P> {
+    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Plan> {
+        let plan = self.planner.plan(env, source, target)?;
+        Some(self.smoother.smooth(env, source, &plan))
+    }
+    fn update(&mut self, env: &S, changes: &[CellChange]) {
+        self.planner.update(env, changes);
+    }
+}
@@ -0,0 +1,142 @@
+use crate::global::types::plan::{CellChange, Plan, Planner, SearchStats};
+use crate::global::types::{ACoord, Belief, Connectivity, HeurHeap, HeurNode, Opacity, SpatialMap};
+use std::collections::{HashMap, HashSet};
+use std::time::Instant;
+
+/// Any-angle A* whose nodes may take any visible ancestor as their parent
+///
+/// Plans are the corners of the path, the straight segments between them are
+/// clear of occupied cells. Lazy Theta* assumes sight while relaxing and only
+/// checks it once a node is expanded, trading slightly longer paths for far
+/// fewer line of sight checks.
+///
+/// # Attributes #
+/// * lazy := whether line of sight is deferred until expansion
+/// * unknown := whether unknown cells block a segment
+pub struct ThetaStarPlanner {
+    pub lazy: bool,
+    pub unknown: Opacity,
+}
+
+impl ThetaStarPlanner {
+    pub fn new() -> Self {
+        Self {
+            lazy: false,
+            unknown: Opacity::Transparent,
+        }
+    }
+    pub fn lazy() -> Self {
+        Self {
+            lazy: true,
+            ..Self::new()
+        }
+    }
+    pub fn with_unknown(mut self, unknown: Opacity) -> Self {
+        self.unknown = unknown;
+        self
+    }
+    fn encode_plan<S: SpatialMap>(
+        &self,
+        env: &S,
+        source: ACoord,
+        target: ACoord,
+        stats: &mut SearchStats,
+    ) -> Option<HashMap<S::Encoded, S::Encoded>> {
+        let (s_encode, t_encode) = (env.encode(source), env.encode(target));
+        // segments start and end at the exact cells rather than the nodes holding them
+        let cell = |n: S::Encoded| match n {
+            n if n == s_encode => source,
+            n if n == t_encode => target,
+            n => env.decode(n),
+        };
+        let length = |a, b| Connectivity::Euclidean.cost(cell(a), cell(b));
+        let visible = |a, b| env.line_of_sight(cell(a), cell(b), self.unknown);
+        let (source, target) = (s_encode, t_encode);
+        let mut p_queue: HeurHeap<S::Encoded> = HeurHeap::new();
+        let mut incurred = HashMap::new();
+        let mut parent = HashMap::new();
+        let mut closed = HashSet::new();
+        incurred.insert(source, 0);
+        parent.insert(source, source);
+        p_queue.push(HeurNode {
+            incurred: 0,
+            cost: length(source, target),
+            coord: source,
+        });
+        stats.pushes += 1;
+        while let Some(node) = p_queue.pop() {
+            if !closed.insert(node.coord) {
+                continue;
+            }
+            stats.expanded += 1;
+            if self.lazy && !visible(parent[&node.coord], node.coord) {
+                // the assumed parent is hidden, fall back to the best expanded neighbour
+                let best = env
+                    .neighbors(node.coord)
+                    .into_iter()
+                    .filter(|n| closed.contains(n))
+                    .map(|n| (incurred[&n] + length(n, node.coord), n))
+                    .min_by_key(|&(cost, _)| cost);
+                if let Some((cost, n)) = best {
+                    incurred.insert(node.coord, cost);
+                    parent.insert(node.coord, n);
+                }
+            }
+            if node.coord == target {
+                parent.remove(&source);
+                return Some(parent);
+            }
+            let ancestor = parent[&node.coord];
+            for n in env.neighbors(node.coord) {
+                if closed.contains(&n) || env.belief(n) == Belief::Occupied {
+                    continue;
+                }
+                // skipping the node when its parent sees the neighbour straightens the path
+                let (from, cost) = if self.lazy || visible(ancestor, n) {
+                    (ancestor, incurred[&ancestor] + length(ancestor, n))
+                } else {
+                    (node.coord, incurred[&node.coord] + length(node.coord, n))
+                };
+                if incurred.get(&n).is_none_or(|&c| cost < c) {
+                    incurred.insert(n, cost);
+                    parent.insert(n, from);
+                    p_queue.push(HeurNode {
+                        incurred: cost,
+                        cost: cost + length(n, target),
+                        coord: n,
+                    });
+                    stats.pushes += 1;
+                }
+            }
+        }
+        None
+    }
+}
+
+impl Default for ThetaStarPlanner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S: SpatialMap> Planner<S> for ThetaStarPlanner {
+    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Plan> {
+        if env.obstructed(target) {
+            return None;
+        };
+        let start = Instant::now();
+        let mut stats = SearchStats::default();
+        let (s_encode, t_encode) = (env.encode(source), env.encode(target));
+        let parent = self.encode_plan(env, source, target, &mut stats)?;
+        let mut plan = vec![];
+        let mut node = t_encode;
+        while node != s_encode {
+            plan.push(if node == t_encode { target } else { env.decode(node) });
+            node = parent[&node];
+        }
+        plan.reverse();
+        stats.elapsed = start.elapsed();
+        Some(Plan::segments(env, source, plan, stats))
+    }
+    fn update(&mut self, _: &S, _: &[CellChange]) {}
+}
@@ -0,0 +1,276 @@
+use crate::global::types::plan::CellChange;
+use crate::global::types::{ACoord, Belief, Bounds, SpatialMap};
+use std::collections::HashSet;
+use std::fmt::Debug;
+
+/// Rays recorded into an empty map before the contract is probed
+///
+/// Every ray runs along a compass direction, the cells it crosses are those
+/// met stepping one unit along each axis at a time.
+pub const RAYS: [(ACoord, ACoord); 6] = [
+    (ACoord { x: 0, y: 0 }, ACoord { x: 6, y: 0 }),
+    (ACoord { x: 0, y: 0 }, ACoord { x: 0, y: 5 }),
+    (ACoord { x: 0, y: 0 }, ACoord { x: -3, y: 0 }),
+    (ACoord { x: 0, y: 0 }, ACoord { x: 0, y: -4 }),
+    (ACoord { x: 0, y: 0 }, ACoord { x: 4, y: 4 }),
+    (ACoord { x: 1, y: 1 }, ACoord { x: -3, y: 5 }),
+];
+
+/// Rays along the six axial directions, straight lines on a hex grid
+///
+/// Axial (1, 1) is not a hex direction, the diagonal ray runs along (1, -1).
+pub const HEX_RAYS: [(ACoord, ACoord); 6] = [
+    (ACoord { x: 0, y: 0 }, ACoord { x: 6, y: 0 }),
+    (ACoord { x: 0, y: 0 }, ACoord { x: 0, y: 5 }),
+    (ACoord { x: 0, y: 0 }, ACoord { x: -3, y: 0 }),
+    (ACoord { x: 0, y: 0 }, ACoord { x: 0, y: -4 }),
+    (ACoord { x: 0, y: 0 }, ACoord { x: 4, y: -4 }),
+    (ACoord { x: 1, y: 1 }, ACoord { x: -3, y: 5 }),
+];
+
+/// Cells probed around the recorded rays, reaching past them into unseen space
+const PROBE: Bounds = Bounds {
+    min_x: -6,
+    min_y: -6,
+    max_x: 9,
+    max_y: 9,
+};
+
+fn probe() -> impl Iterator<Item = ACoord> {
+    (PROBE.min_x..=PROBE.max_x)
+        .flat_map(|x| (PROBE.min_y..=PROBE.max_y).map(move |y| ACoord { x, y }))
+}
+
+/// Cells the map holds a belief for
+fn covered<S: SpatialMap>(map: &S) -> HashSet<ACoord> {
+    let mut cells = HashSet::new();
+    for region in map.regions() {
+        let rect = region.bounds;
+        for x in rect.min_x..=rect.max_x {
+            for y in rect.min_y..=rect.max_y {
+                cells.insert(ACoord { x, y });
+            }
+        }
+    }
+    cells
+}
+
+fn observed<S: SpatialMap>(factory: impl Fn() -> S, rays: &[(ACoord, ACoord)]) -> S {
+    let mut map = factory();
+    for &(pos, hit) in rays {
+        map.insert_ray(pos, hit);
+    }
+    map
+}
+
+/// Runs every clause of the SpatialMap contract against fresh maps
+///
+/// # Example Usage
+/// ```
+///  # use sabrina::environment::conformance::check_spatial_map;
+///  # use sabrina::environment::grid::Grid;
+///     check_spatial_map(Grid::new);
+/// ```
+pub fn check_spatial_map<S>(factory: impl Fn() -> S)
+where
+    S: SpatialMap,
+    S::Encoded: Debug,
+{
+    check_spatial_map_with(factory, &RAYS);
+}
+
+/// Runs the contract with rays straight in the geometry of the map
+///
+/// # Example Usage
+/// ```
+///  # use sabrina::environment::conformance::{HEX_RAYS, check_spatial_map_with};
+///  # use sabrina::environment::hex::HexGrid;
+///     check_spatial_map_with(HexGrid::new, &HEX_RAYS);
+/// ```
+pub fn check_spatial_map_with<S>(factory: impl Fn() -> S, rays: &[(ACoord, ACoord)])
+where
+    S: SpatialMap,
+    S::Encoded: Debug,
+{
+    check_encoding(&factory, rays);
+    check_insert_ray(&factory, rays);
+    check_obstructed(&factory, rays);
+    check_neighbors(&factory, rays);
+    check_distance(&factory, rays);
+    check_regions(&factory, rays);
+}
+
+/// Encoding is stable and agrees with the leaf of the cell
+pub fn check_encoding<S>(factory: impl Fn() -> S, rays: &[(ACoord, ACoord)])
+where
+    S: SpatialMap,
+    S::Encoded: Debug,
+{
+    let map = observed(factory, rays);
+    for coord in probe() {
+        let (node, leaf) = (map.encode(coord), map.leaf(coord));
+        assert_eq!(
+            map.decode(leaf),
+            coord,
+            "leaf {leaf:?} must decode to its cell"
+        );
+        assert_eq!(
+            map.encode(map.decode(node)),
+            node,
+            "encoding of {coord:?} must be stable"
+        );
+        assert_eq!(
+            map.belief(node),
+            map.belief(leaf),
+            "node {node:?} and leaf {leaf:?} must share the belief of {coord:?}"
+        );
+    }
+}
+
+/// Cells traversed by a ray are free, the hit is occupied and the changes are reported
+pub fn check_insert_ray<S>(factory: impl Fn() -> S, rays: &[(ACoord, ACoord)])
+where
+    S: SpatialMap,
+    S::Encoded: Debug,
+{
+    let map = observed(&factory, rays);
+    for &(mut pos, hit) in rays {
+        let (del_x, del_y) = ((hit.x - pos.x).signum(), (hit.y - pos.y).signum());
+        pos.x += del_x;
+        pos.y += del_y;
+        while pos != hit {
+            if !rays.iter().any(|&(_, h)| h == pos) {
+                let belief = map.belief(map.encode(pos));
+                assert_eq!(belief, Belief::Free, "ray through {pos:?} must free it");
+            }
+            pos.x += del_x;
+            pos.y += del_y;
+        }
+        let belief = map.belief(map.encode(hit));
+        assert_eq!(belief, Belief::Occupied, "ray hit {hit:?} must be occupied");
+    }
+    // every cell whose belief a ray changed is reported, and nothing else
+    let mut map = factory();
+    for &(pos, hit) in rays {
+        let before: Vec<Belief> = probe().map(|c| map.belief(map.encode(c))).collect();
+        let mut reported = map.insert_ray(pos, hit);
+        let mut changed: Vec<CellChange> = probe()
+            .zip(before)
+            .map(|(coord, old)| CellChange {
+                coord,
+                old,
+                new: map.belief(map.encode(coord)),
+            })
+            .filter(|change| change.old != change.new)
+            .collect();
+        reported.sort_by_key(|c| (c.coord.x, c.coord.y));
+        changed.sort_by_key(|c| (c.coord.x, c.coord.y));
+        assert_eq!(reported, changed, "ray {pos:?} -> {hit:?} must report its changes");
+    }
+}
+
+/// Obstruction agrees with the belief of the encoded cell
+pub fn check_obstructed<S>(factory: impl Fn() -> S, rays: &[(ACoord, ACoord)])
+where
+    S: SpatialMap,
+    S::Encoded: Debug,
+{
+    let map = observed(factory, rays);
+    for coord in probe() {
+        let occupied = map.belief(map.encode(coord)) == Belief::Occupied;
+        assert_eq!(
+            map.obstructed(coord),
+            occupied,
+            "obstructed {coord:?} must agree with its belief"
+        );
+    }
+}
+
+/// Neighbours are never occupied and adjacency is symmetric within known space
+pub fn check_neighbors<S>(factory: impl Fn() -> S, rays: &[(ACoord, ACoord)])
+where
+    S: SpatialMap,
+    S::Encoded: Debug,
+{
+    let map = observed(factory, rays);
+    let known = covered(&map);
+    for coord in probe().filter(|c| known.contains(c)) {
+        let node = map.encode(coord);
+        if map.belief(node) == Belief::Occupied {
+            continue;
+        }
+        for neigh in map.neighbors(node) {
+            assert_ne!(
+                map.belief(neigh),
+                Belief::Occupied,
+                "neighbour {neigh:?} of {node:?} is occupied"
+            );
+            assert_ne!(neigh, node, "{node:?} must not neighbour itself");
+            assert!(
+                map.neighbors(neigh).contains(&node),
+                "{node:?} -> {neigh:?} must be symmetric"
+            );
+        }
+    }
+}
+
+/// Distance is a metric over neighbouring nodes
+pub fn check_distance<S>(factory: impl Fn() -> S, rays: &[(ACoord, ACoord)])
+where
+    S: SpatialMap,
+    S::Encoded: Debug,
+{
+    let map = observed(factory, rays);
+    let anchor = map.encode(ACoord { x: 0, y: 0 });
+    for coord in probe() {
+        let node = map.encode(coord);
+        assert_eq!(
+            map.distance(node, node),
+            0,
+            "{node:?} must be 0 from itself"
+        );
+        for neigh in map.neighbors(node) {
+            let (d, r) = (map.distance(node, neigh), map.distance(neigh, node));
+            assert!(d > 0, "distinct {node:?} and {neigh:?} must be apart");
+            assert_eq!(d, r, "distance {node:?} <-> {neigh:?} must be symmetric");
+            assert!(
+                map.distance(anchor, neigh) <= map.distance(anchor, node) + d,
+                "distance via {node:?} -> {neigh:?} violates the triangle inequality"
+            );
+        }
+    }
+}
+
+/// Exported regions carry the belief of their cells and can be filled
+pub fn check_regions<S>(factory: impl Fn() -> S, rays: &[(ACoord, ACoord)])
+where
+    S: SpatialMap,
+    S::Encoded: Debug,
+{
+    let mut map = observed(factory, rays);
+    for region in map.regions() {
+        let rect = &region.bounds;
+        for x in rect.min_x..=rect.max_x {
+            for y in rect.min_y..=rect.max_y {
+                let node = map.encode(ACoord { x, y });
+                assert_eq!(
+                    map.belief(node),
+                    region.belief,
+                    "region {rect:?} at {node:?}"
+                );
+            }
+        }
+    }
+    let rect = Bounds {
+        min_x: -2,
+        min_y: 3,
+        max_x: 5,
+        max_y: 7,
+    };
+    map.fill_region(&rect, Belief::Occupied);
+    for coord in probe() {
+        if rect.contains(coord) {
+            assert!(map.obstructed(coord), "filled {coord:?} must be occupied");
+        }
+    }
+}
@@ -0,0 +1,72 @@
+use crate::environment::grid::Grid;
+use crate::environment::quad::QuadTree;
+use crate::environment::quadrant_grid::QuadrantGrid;
+use crate::global::types::{ACoord, Belief, Bounds, SpatialMap};
+use std::collections::HashMap;
+
+/// Copies every belief region of the source into the destination
+///
+/// Lossless over the exported regions, cells outside them keep whatever
+/// default the destination assumes for unrecorded space.
+pub fn transfer<A, B>(source: &A, destination: &mut B)
+where
+    A: SpatialMap,
+    B: SpatialMap,
+{
+    for region in source.regions() {
+        destination.fill_region(&region.bounds, region.belief);
+    }
+}
+
+impl Grid {
+    /// Dense grid holding the beliefs of another representation
+    pub fn from_map<M: SpatialMap>(map: &M) -> Self {
+        let mut grid = Self::new();
+        transfer(map, &mut grid);
+        grid
+    }
+}
+
+impl QuadrantGrid {
+    /// Quadrant grid holding the beliefs of another representation
+    pub fn from_map<M: SpatialMap>(map: &M) -> Self {
+        let mut quadrant = Self::new();
+        transfer(map, &mut quadrant);
+        quadrant
+    }
+}
+
+impl QuadTree {
+    /// Compressed quadtree holding the beliefs of another representation
+    ///
+    /// # Arguments #
+    /// * levels := compression depth, top level nodes span 2^(levels - 1) cells
+    pub fn from_map<M: SpatialMap>(map: &M, levels: usize) -> Self {
+        let mut cells = HashMap::new();
+        let mut hull = Bounds {
+            min_x: isize::MAX,
+            min_y: isize::MAX,
+            max_x: isize::MIN,
+            max_y: isize::MIN,
+        };
+        for region in map.regions() {
+            let rect = region.bounds;
+            hull.min_x = hull.min_x.min(rect.min_x);
+            hull.min_y = hull.min_y.min(rect.min_y);
+            hull.max_x = hull.max_x.max(rect.max_x);
+            hull.max_y = hull.max_y.max(rect.max_y);
+            for x in rect.min_x..=rect.max_x {
+                for y in rect.min_y..=rect.max_y {
+                    cells.insert(ACoord { x, y }, region.belief);
+                }
+            }
+        }
+        if cells.is_empty() {
+            return Self::init(levels);
+        }
+        Self::from_fn(levels, &hull, |coord| match cells.get(&coord) {
+            Some(&belief) => belief,
+            None => Belief::Unknown,
+        })
+    }
+}
@@ -1,28 +1,29 @@
+use crate::global::line::bresenham;
+use crate::global::types::plan::CellChange;
 use crate::global::types::{ACoord, Belief, Bounds};
-use crate::global::types::{CARDINALS, SpatialMap};
+use crate::global::types::{Connectivity, Region, SpatialMap};
 use std::collections::HashMap;
 
 #[derive(Clone)]
 pub struct Grid {
     pub information: HashMap<ACoord, Belief>,
     pub bounds: Bounds,
+    pub connectivity: Connectivity,
 }
 impl SpatialMap for Grid {
     type Encoded = ACoord;
-    fn insert_ray(&mut self, mut pos: ACoord, hit: ACoord) {
+    fn insert_ray(&mut self, pos: ACoord, hit: ACoord) -> Vec<CellChange> {
         // beliefs not recorded are assumed free
-        // handles simulation compass rose signals
-        let (dy, dx) = (hit.y - pos.y, hit.x - pos.x);
-        let (del_y, del_x) = (dy.signum(), dx.signum());
-        pos.x += del_x;
-        pos.y += del_y;
-        while pos != hit {
-            self.information.insert(pos, Belief::Free);
-            pos.x += del_x;
-            pos.y += del_y;
+        // walks the straight line to the hit, diagonally where it slants
+        let mut changes = Vec::new();
+        for cell in bresenham(pos, hit).into_iter().skip(1).take_while(|&c| c != hit) {
+            let old = self.information.insert(cell, Belief::Free);
+            changes.extend(CellChange::between(cell, old.unwrap_or(Belief::Free), Belief::Free));
         }
         self.update_bounds(hit);
-        self.information.insert(hit, Belief::Occupied);
+        let old = self.information.insert(hit, Belief::Occupied);
+        changes.extend(CellChange::between(hit, old.unwrap_or(Belief::Free), Belief::Occupied));
+        changes
     }
     fn obstructed(&self, coord: ACoord) -> bool {
         // false
@@ -38,16 +39,17 @@ impl SpatialMap for Grid {
         node
     }
     fn distance(&self, a: ACoord, b: ACoord) -> usize {
-        a.x.abs_diff(b.x).wrapping_add(a.y.abs_diff(b.y))
+        self.connectivity.cost(a, b)
     }
     fn neighbors(&self, node: Self::Encoded) -> Vec<ACoord> {
         let mut valid = Vec::new();
-        for d in CARDINALS {
+        let occupied = |c| self.belief(c) == Belief::Occupied;
+        for &d in self.connectivity.directions() {
             let n_xy = ACoord {
                 x: node.x + d.x,
                 y: node.y + d.y,
             };
-            if self.belief(n_xy) != Belief::Occupied {
+            if !occupied(n_xy) && !Connectivity::cuts_corner(node, d, occupied) {
                 valid.push(n_xy);
             }
         }
@@ -60,6 +62,53 @@ impl SpatialMap for Grid {
         }
     }
     fn initialize(&mut self, _source: ACoord, _target: ACoord) {}
+    fn regions(&self) -> Vec<Region> {
+        // unrecorded cells within bounds are believed free
+        let mut regions = Vec::new();
+        for x in self.bounds.min_x..=self.bounds.max_x {
+            for y in self.bounds.min_y..=self.bounds.max_y {
+                let coord = ACoord { x, y };
+                regions.push(Region {
+                    bounds: Bounds {
+                        min_x: x,
+                        min_y: y,
+                        max_x: x,
+                        max_y: y,
+                    },
+                    belief: SpatialMap::belief(self, coord),
+                });
+            }
+        }
+        for (&coord, &belief) in &self.information {
+            if !self.bounds.contains(coord) {
+                regions.push(Region {
+                    bounds: Bounds {
+                        min_x: coord.x,
+                        min_y: coord.y,
+                        max_x: coord.x,
+                        max_y: coord.y,
+                    },
+                    belief,
+                });
+            }
+        }
+        regions
+    }
+    fn fill_region(&mut self, rect: &Bounds, belief: Belief) {
+        for x in rect.min_x..=rect.max_x {
+            for y in rect.min_y..=rect.max_y {
+                self.information.insert(ACoord { x, y }, belief);
+            }
+        }
+        self.update_bounds(ACoord {
+            x: rect.min_x,
+            y: rect.min_y,
+        });
+        self.update_bounds(ACoord {
+            x: rect.max_x,
+            y: rect.max_y,
+        });
+    }
 }
 
 impl Grid {
@@ -77,36 +126,17 @@ impl Grid {
         Self {
             information: HashMap::new(),
             bounds,
+            connectivity: Connectivity::Four,
         }
     }
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
     pub fn update_bounds(&mut self, node: ACoord) {
         self.bounds.min_x = self.bounds.min_x.min(node.x);
         self.bounds.min_y = self.bounds.min_y.min(node.y);
         self.bounds.max_x = self.bounds.max_x.max(node.x);
         self.bounds.max_y = self.bounds.max_y.max(node.y);
     }
-    pub fn belief(&self, xy: ACoord) -> Belief {
-        match self.information.get(&xy) {
-            Some(&belief) => belief,
-            None => Belief::Unknown,
-        }
-    }
-    pub fn raycast(&self, position: ACoord, delta: ACoord, max_range: usize) -> Option<ACoord> {
-        // mock interface owning interface don't need dynamic changing env at the moment
-        // RcRefcell or ArcMutex if doing pathing with multiple as extensions
-        let mut n_xy = position;
-        for _ in 0..max_range {
-            n_xy.x += delta.x;
-            n_xy.y += delta.y;
-            // needs to fit wrt the underlying grid
-            if self.belief(n_xy) == Belief::Occupied {
-                // denormalize b/c is oracle and needs to be relative
-                return Some(ACoord {
-                    x: n_xy.x - position.x,
-                    y: n_xy.y - position.y,
-                });
-            }
-        }
-        None
-    }
 }
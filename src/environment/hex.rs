@@ -0,0 +1,141 @@
+use crate::global::line::{hex_distance, hex_line};
+use crate::global::types::plan::CellChange;
+use crate::global::types::{ACoord, Belief, Bounds, HEX_DIRECTIONS, Opacity};
+use crate::global::types::{Region, SpatialMap};
+use std::collections::HashMap;
+
+/// Hex tessellated occupancy map in axial coordinates
+///
+/// ACoord is read as x := q (column axis), y := r (row axis). Each cell has
+/// six neighbours along HEX_DIRECTIONS. Rectangles of the interchange API
+/// are taken over the axial coordinates.
+#[derive(Clone)]
+pub struct HexGrid {
+    pub information: HashMap<ACoord, Belief>,
+    pub bounds: Bounds,
+}
+
+impl SpatialMap for HexGrid {
+    type Encoded = ACoord;
+    fn insert_ray(&mut self, pos: ACoord, hit: ACoord) -> Vec<CellChange> {
+        // beliefs not recorded are assumed free
+        let mut changes = Vec::new();
+        for cell in hex_line(pos, hit) {
+            if cell != pos && cell != hit {
+                let old = self.information.insert(cell, Belief::Free).unwrap_or(Belief::Free);
+                changes.extend(CellChange::between(cell, old, Belief::Free));
+            }
+        }
+        self.update_bounds(hit);
+        let old = self.information.insert(hit, Belief::Occupied);
+        changes.extend(CellChange::between(hit, old.unwrap_or(Belief::Free), Belief::Occupied));
+        changes
+    }
+    fn obstructed(&self, coord: ACoord) -> bool {
+        self.belief(coord) == Belief::Occupied
+    }
+    fn encode(&self, coord: ACoord) -> ACoord {
+        coord
+    }
+    fn leaf(&self, coord: ACoord) -> ACoord {
+        coord
+    }
+    fn decode(&self, node: ACoord) -> ACoord {
+        node
+    }
+    fn distance(&self, a: ACoord, b: ACoord) -> usize {
+        hex_distance(a, b)
+    }
+    fn segment(&self, a: ACoord, b: ACoord) -> usize {
+        // a straight line of hexes takes as many steps as the shortest walk
+        hex_distance(a, b)
+    }
+    fn neighbors(&self, node: ACoord) -> Vec<ACoord> {
+        let mut valid = Vec::new();
+        for d in HEX_DIRECTIONS {
+            let n_qr = ACoord {
+                x: node.x + d.x,
+                y: node.y + d.y,
+            };
+            if self.belief(n_qr) != Belief::Occupied {
+                valid.push(n_qr);
+            }
+        }
+        valid
+    }
+    fn belief(&self, node: ACoord) -> Belief {
+        match self.information.get(&node) {
+            Some(&belief) => belief,
+            None => Belief::Free,
+        }
+    }
+    fn initialize(&mut self, _source: ACoord, _target: ACoord) {}
+    fn regions(&self) -> Vec<Region> {
+        self.information
+            .iter()
+            .map(|(&coord, &belief)| Region {
+                bounds: Bounds {
+                    min_x: coord.x,
+                    min_y: coord.y,
+                    max_x: coord.x,
+                    max_y: coord.y,
+                },
+                belief,
+            })
+            .collect()
+    }
+    fn fill_region(&mut self, rect: &Bounds, belief: Belief) {
+        for q in rect.min_x..=rect.max_x {
+            for r in rect.min_y..=rect.max_y {
+                let coord = ACoord { x: q, y: r };
+                self.update_bounds(coord);
+                self.information.insert(coord, belief);
+            }
+        }
+    }
+    fn line_of_sight(&self, a: ACoord, b: ACoord, unknown: Opacity) -> bool {
+        hex_line(a, b)
+            .into_iter()
+            .all(|c| !self.belief(c).blocks(unknown))
+    }
+}
+
+impl HexGrid {
+    pub fn new() -> Self {
+        let bounds = Bounds {
+            min_x: isize::MAX,
+            min_y: isize::MAX,
+            max_x: isize::MIN,
+            max_y: isize::MIN,
+        };
+        Self {
+            information: HashMap::new(),
+            bounds,
+        }
+    }
+    pub fn update_bounds(&mut self, node: ACoord) {
+        self.bounds.min_x = self.bounds.min_x.min(node.x);
+        self.bounds.min_y = self.bounds.min_y.min(node.y);
+        self.bounds.max_x = self.bounds.max_x.max(node.x);
+        self.bounds.max_y = self.bounds.max_y.max(node.y);
+    }
+}
+
+impl Default for HexGrid {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Odd rows shifted right, (col, row) -> axial
+pub fn offset_to_axial(col: isize, row: isize) -> ACoord {
+    ACoord {
+        x: col - (row - (row & 1)) / 2,
+        y: row,
+    }
+}
+
+/// Axial -> odd rows shifted right, (col, row)
+pub fn axial_to_offset(coord: ACoord) -> (isize, isize) {
+    (coord.x + (coord.y - (coord.y & 1)) / 2, coord.y)
+}
@@ -0,0 +1,34 @@
+use crate::environment::hex::{HexGrid, axial_to_offset, offset_to_axial};
+use crate::global::types::Belief;
+use std::fmt;
+
+impl fmt::Display for HexGrid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // odd rows are indented to stagger the cells
+        let (mut min_col, mut max_col) = (isize::MAX, isize::MIN);
+        for &coord in self.information.keys() {
+            let (col, _) = axial_to_offset(coord);
+            min_col = min_col.min(col);
+            max_col = max_col.max(col);
+        }
+        for row in (self.bounds.min_y..=self.bounds.max_y).rev() {
+            let mut line = String::new();
+            if row & 1 == 1 {
+                line.push(' ');
+            }
+            for col in min_col..=max_col {
+                let symbol = match self.information.get(&offset_to_axial(col, row)) {
+                    None => ' ',
+                    Some(Belief::Free) => ' ',
+                    Some(Belief::Occupied) => '#',
+                    Some(Belief::Unknown) => '?',
+                };
+                line.push('[');
+                line.push(symbol);
+                line.push(']');
+            }
+            writeln!(f, "{}", line)?;
+        }
+        Ok(())
+    }
+}
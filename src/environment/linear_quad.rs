@@ -0,0 +1,382 @@
+use crate::environment::quad::{QuadLeaf, QuadTree};
+use crate::global::consts::LEVELS;
+use crate::global::line::bresenham;
+use crate::global::types::plan::CellChange;
+use crate::global::types::{ACoord, Belief, Bounds, Connectivity, HCoord, Region, SpatialMap};
+use crate::hierarchy::encoding::{extent, point};
+use crate::hierarchy::morton::{children, locate, morton, node, parent, span, step};
+use std::collections::BTreeMap;
+
+/// Homogeneous leaf stored under its locational code
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct LinearNode {
+    pub level: usize,
+    pub belief: Belief,
+}
+
+/// Linear quadtree, leaves sorted by Morton code
+///
+/// Leaves partition the covered space so the leaf containing a cell is the
+/// greatest code not past the cell's code, lookups don't walk the levels.
+/// Behaves as the QuadTree under SpatialMap and shares its HCoord encoding.
+///
+/// # Attributes #
+/// * information := locational code -> leaf starting at the code
+/// * levels := depth of the tree, top-level leaves span 2^(levels - 1)
+#[derive(Clone)]
+pub struct LinearQuadTree {
+    pub information: BTreeMap<u64, LinearNode>,
+    pub bounds: Bounds,
+    pub levels: usize,
+    pub connectivity: Connectivity,
+}
+
+impl SpatialMap for LinearQuadTree {
+    type Encoded = HCoord;
+    fn encode(&self, coord: ACoord) -> Self::Encoded {
+        match self.containing(morton(coord)) {
+            Some((code, n)) => node(code, n.level),
+            None => self.leaf(coord),
+        }
+    }
+    fn decode(&self, node: Self::Encoded) -> ACoord {
+        ACoord {
+            x: node.x,
+            y: node.y,
+        }
+    }
+    fn leaf(&self, coord: ACoord) -> Self::Encoded {
+        HCoord {
+            l: 0,
+            x: coord.x,
+            y: coord.y,
+        }
+    }
+    fn covering(&self, node: Self::Encoded) -> Vec<Self::Encoded> {
+        let rect = extent(&node);
+        self.leaves()
+            .filter(|leaf| leaf.bounds.intersects(&rect))
+            .map(|leaf| leaf.node)
+            .collect()
+    }
+    fn initialize(&mut self, _source: ACoord, target: ACoord) {
+        // mirrors the QuadTree until it resizes intelligently
+        let span = 1 << (self.levels - 1);
+        let min_x = target.x.min(self.bounds.min_x);
+        let min_y = target.y.min(self.bounds.min_y);
+        for x in (min_x..=24).step_by(span) {
+            for y in (min_y..=12).step_by(span) {
+                self.update_bounds(ACoord { x, y });
+            }
+        }
+    }
+    fn obstructed(&self, coord: ACoord) -> bool {
+        matches!(self.get_coord(coord), Some((_, Belief::Occupied)))
+    }
+    fn distance(&self, a: Self::Encoded, b: Self::Encoded) -> usize {
+        // distance between source-centroid and target-centroid
+        self.connectivity.cost(point(a), point(b))
+    }
+    fn neighbors(&self, a: Self::Encoded) -> Vec<Self::Encoded> {
+        let mut neighbors = self.edge_neighbors(a);
+        if self.connectivity != Connectivity::Four {
+            for n in self.corner_neighbors(a) {
+                if !neighbors.contains(&n) {
+                    neighbors.push(n);
+                }
+            }
+        }
+        neighbors
+    }
+    fn belief(&self, node: Self::Encoded) -> Belief {
+        match self.get_node(node) {
+            Some((_, belief)) => belief,
+            None => Belief::Unknown,
+        }
+    }
+    fn insert_ray(&mut self, pos: ACoord, hit: ACoord) -> Vec<CellChange> {
+        // beliefs not recorded are assumed unknown
+        // walks the straight line to the hit, diagonally where it slants
+        let mut changes = Vec::new();
+        for cell in bresenham(pos, hit).into_iter().skip(1).take_while(|&c| c != hit) {
+            let old = self.belief(self.encode(cell));
+            self.update_belief(&cell, Belief::Free);
+            changes.extend(CellChange::between(cell, old, Belief::Free));
+        }
+        let old = self.belief(self.encode(hit));
+        self.update_belief(&hit, Belief::Occupied);
+        changes.extend(CellChange::between(hit, old, Belief::Occupied));
+        changes
+    }
+    fn regions(&self) -> Vec<Region> {
+        self.leaves()
+            .map(|leaf| Region {
+                bounds: leaf.bounds,
+                belief: leaf.belief,
+            })
+            .collect()
+    }
+    fn fill_region(&mut self, rect: &Bounds, belief: Belief) {
+        self.fill_rect(rect, belief);
+    }
+}
+
+impl LinearQuadTree {
+    pub fn new() -> Self {
+        Self::init(LEVELS)
+    }
+    pub fn init(levels: usize) -> Self {
+        // level 0 contains no shift and level is inclusive
+        let stride = 1 << (levels - 1);
+        let mut information = BTreeMap::new();
+        information.insert(
+            morton(ACoord { x: 0, y: 0 }),
+            LinearNode {
+                level: levels - 1,
+                belief: Belief::Unknown,
+            },
+        );
+        Self {
+            information,
+            bounds: Bounds {
+                min_x: 0,
+                min_y: 0,
+                max_x: stride - 1,
+                max_y: stride - 1,
+            },
+            levels,
+            connectivity: Connectivity::Four,
+        }
+    }
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
+    /// Linear copy of the leaves of a quadtree
+    pub fn from_quad(quad: &QuadTree) -> Self {
+        let mut information = BTreeMap::new();
+        for leaf in quad.leaves() {
+            information.insert(
+                locate(leaf.node),
+                LinearNode {
+                    level: leaf.node.l,
+                    belief: leaf.belief,
+                },
+            );
+        }
+        Self {
+            information,
+            bounds: quad.bounds.clone(),
+            levels: quad.levels,
+            connectivity: quad.connectivity,
+        }
+    }
+    /// Leaf owning the code
+    pub fn containing(&self, code: u64) -> Option<(u64, LinearNode)> {
+        let (&start, &n) = self.information.range(..=code).next_back()?;
+        (code - start < span(n.level)).then_some((start, n))
+    }
+    pub fn get_coord(&self, coord: ACoord) -> Option<(usize, Belief)> {
+        self.containing(morton(coord)).map(|(_, n)| (n.level, n.belief))
+    }
+    /// Leaf covering the node, when it is at least as coarse as the node
+    pub fn get_node(&self, node: HCoord) -> Option<(usize, Belief)> {
+        match self.containing(locate(node)) {
+            Some((_, n)) if n.level >= node.l => Some((n.level, n.belief)),
+            _ => None,
+        }
+    }
+    /// Homogeneous leaves in Z-order
+    pub fn leaves(&self) -> impl Iterator<Item = QuadLeaf> + '_ {
+        self.information.iter().map(|(&code, n)| {
+            let leaf = node(code, n.level);
+            QuadLeaf {
+                node: leaf,
+                belief: n.belief,
+                bounds: extent(&leaf),
+            }
+        })
+    }
+    fn update_bounds(&mut self, coord: ACoord) {
+        if self.get_coord(coord).is_some() {
+            return;
+        }
+        let top = self.levels - 1;
+        let span = 1 << top;
+        let block = node(morton(coord), top);
+        self.bounds.min_x = self.bounds.min_x.min(block.x);
+        self.bounds.min_y = self.bounds.min_y.min(block.y);
+        self.bounds.max_x = self.bounds.max_x.max(block.x + span);
+        self.bounds.max_y = self.bounds.max_y.max(block.y + span);
+        self.information.insert(
+            locate(block),
+            LinearNode {
+                level: top,
+                belief: Belief::Unknown,
+            },
+        );
+    }
+    /// Splits the covering leaf down to the cell and merges siblings back up
+    pub fn update_belief(&mut self, coord: &ACoord, belief: Belief) {
+        self.update_bounds(*coord);
+        let code = morton(*coord);
+        let Some((mut start, current)) = self.containing(code) else {
+            return;
+        };
+        if current.belief == belief {
+            return;
+        }
+        for level in (1..=current.level).rev() {
+            self.split(start, level, current.belief);
+            start = parent(code, level - 1);
+        }
+        self.information.insert(code, LinearNode { level: 0, belief });
+        for level in 1..self.levels {
+            if !self.merge(parent(code, level), level) {
+                break;
+            }
+        }
+    }
+    /// Sets the belief of every cell in the inclusive rectangle
+    pub fn fill_rect(&mut self, rect: &Bounds, belief: Belief) {
+        let top = self.levels - 1;
+        let span = 1 << top;
+        let (min, max) = (
+            node(morton(ACoord { x: rect.min_x, y: rect.min_y }), top),
+            node(morton(ACoord { x: rect.max_x, y: rect.max_y }), top),
+        );
+        for x in (min.x..=max.x).step_by(span) {
+            for y in (min.y..=max.y).step_by(span) {
+                // regions never observed are opened as unknown like update_bounds
+                self.update_bounds(ACoord { x, y });
+                self.fill_node(morton(ACoord { x, y }), top, rect, belief);
+            }
+        }
+    }
+    fn fill_node(&mut self, code: u64, level: usize, rect: &Bounds, belief: Belief) {
+        if rect.encloses(&extent(&node(code, level))) {
+            self.prune(code, level);
+            self.information.insert(code, LinearNode { level, belief });
+            return;
+        }
+        if let Some(&n) = self.information.get(&code)
+            && n.level == level
+        {
+            if n.belief == belief {
+                return;
+            }
+            self.split(code, level, n.belief);
+        }
+        for child in children(code, level) {
+            if extent(&node(child, level - 1)).intersects(rect) {
+                self.fill_node(child, level - 1, rect, belief);
+            }
+        }
+        self.merge(code, level);
+    }
+    fn split(&mut self, code: u64, level: usize, belief: Belief) {
+        self.information.remove(&code);
+        for child in children(code, level) {
+            self.information.insert(
+                child,
+                LinearNode {
+                    level: level - 1,
+                    belief,
+                },
+            );
+        }
+    }
+    /// Removes every leaf within the node
+    fn prune(&mut self, code: u64, level: usize) {
+        let within: Vec<u64> = self
+            .information
+            .range(code..code + span(level))
+            .map(|(&c, _)| c)
+            .collect();
+        for c in within {
+            self.information.remove(&c);
+        }
+    }
+    /// Replaces four leaf children sharing a belief with their parent
+    fn merge(&mut self, code: u64, level: usize) -> bool {
+        let mut shared = None;
+        for child in children(code, level) {
+            match (self.information.get(&child), shared) {
+                (Some(n), None) if n.level == level - 1 => shared = Some(n.belief),
+                (Some(n), Some(belief)) if n.level == level - 1 && n.belief == belief => {}
+                _ => return false,
+            }
+        }
+        let Some(belief) = shared else {
+            return false;
+        };
+        self.prune(code, level);
+        self.information.insert(code, LinearNode { level, belief });
+        true
+    }
+    /// Unoccupied leaves sharing an edge with the node
+    fn edge_neighbors(&self, a: HCoord) -> Vec<HCoord> {
+        let (code, size) = (locate(a), 1 << a.l);
+        let mut neighbors = Vec::new();
+        for (dx, dy) in [(1, 0), (0, 1), (-1, 0), (0, -1)] {
+            let adjacent = step(code, dx * size, dy * size);
+            if let Some((start, n)) = self.containing(adjacent)
+                && n.level >= a.l
+            {
+                if n.belief != Belief::Occupied {
+                    neighbors.push(node(start, n.level));
+                }
+                continue;
+            }
+            // finer leaves are contiguous within the adjacent node
+            let edge = node(adjacent, a.l);
+            for (&start, n) in self.information.range(adjacent..adjacent + span(a.l)) {
+                let leaf = node(start, n.level);
+                let reach = 1 << n.level;
+                let touches = match (dx, dy) {
+                    (1, 0) => leaf.x == edge.x,
+                    (-1, 0) => leaf.x + reach == edge.x + size,
+                    (0, 1) => leaf.y == edge.y,
+                    _ => leaf.y + reach == edge.y + size,
+                };
+                if touches && n.belief != Belief::Occupied {
+                    neighbors.push(leaf);
+                }
+            }
+        }
+        neighbors
+    }
+    /// Leaves diagonally across the corners reachable without cutting a corner
+    fn corner_neighbors(&self, a: HCoord) -> Vec<HCoord> {
+        let rect = extent(&a);
+        let corners = [
+            (rect.max_x, rect.max_y, ACoord { x: 1, y: 1 }),
+            (rect.min_x, rect.max_y, ACoord { x: -1, y: 1 }),
+            (rect.min_x, rect.min_y, ACoord { x: -1, y: -1 }),
+            (rect.max_x, rect.min_y, ACoord { x: 1, y: -1 }),
+        ];
+        let mut neighbors = Vec::new();
+        for (x, y, d) in corners {
+            let corner = ACoord { x, y };
+            if Connectivity::cuts_corner(corner, d, |c| self.obstructed(c)) {
+                continue;
+            }
+            let diagonal = ACoord {
+                x: x + d.x,
+                y: y + d.y,
+            };
+            if let Some((start, n)) = self.containing(morton(diagonal))
+                && n.belief != Belief::Occupied
+            {
+                neighbors.push(node(start, n.level));
+            }
+        }
+        neighbors
+    }
+}
+
+impl Default for LinearQuadTree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,5 +1,14 @@
+pub mod conformance;
+pub mod convert;
 pub mod grid;
 pub mod grid_display;
+pub mod hex;
+pub mod hex_display;
+pub mod linear_quad;
+pub mod octree;
 pub mod quad;
+pub mod quad_balance;
+pub mod quad_build;
 pub mod quad_display;
+pub mod quad_validate;
 pub mod quadrant_grid;
@@ -0,0 +1,229 @@
+use crate::global::consts::LEVELS;
+use crate::global::types::{Belief, FACES, OCoord, VCoord, VolumetricMap};
+use crate::hierarchy::octal::{child_hier, encode, point, transform};
+use std::collections::HashMap;
+
+/// Sparse octree, the voxel counterpart of the QuadTree
+///
+/// # Attributes #
+/// * information := homogeneous leaf -> belief shared by its voxels
+/// * levels := depth of the tree, top-level leaves span 2^(levels - 1)
+#[derive(Clone)]
+pub struct Octree {
+    pub information: HashMap<OCoord, Belief>,
+    pub levels: usize,
+}
+
+impl VolumetricMap for Octree {
+    type Encoded = OCoord;
+    fn insert_ray(&mut self, mut pos: VCoord, hit: VCoord) {
+        // beliefs not recorded are assumed unknown
+        // handles simulation voxel rose signals
+        let (del_x, del_y, del_z) = (
+            (hit.x - pos.x).signum(),
+            (hit.y - pos.y).signum(),
+            (hit.z - pos.z).signum(),
+        );
+        pos.x += del_x;
+        pos.y += del_y;
+        pos.z += del_z;
+        while pos != hit {
+            self.update_belief(pos, Belief::Free);
+            pos.x += del_x;
+            pos.y += del_y;
+            pos.z += del_z;
+        }
+        self.update_belief(hit, Belief::Occupied);
+    }
+    fn obstructed(&self, coord: VCoord) -> bool {
+        matches!(self.get_coord(coord), Some((_, Belief::Occupied)))
+    }
+    fn belief(&self, node: Self::Encoded) -> Belief {
+        match self.get_node(node) {
+            Some((_, belief)) => belief,
+            None => Belief::Unknown,
+        }
+    }
+    fn neighbors(&self, node: Self::Encoded) -> Vec<Self::Encoded> {
+        self.face_neighbors(node)
+    }
+    fn distance(&self, a: Self::Encoded, b: Self::Encoded) -> usize {
+        // distance between source-centroid and target-centroid
+        let (a, b) = (point(a), point(b));
+        a.x.abs_diff(b.x) + a.y.abs_diff(b.y) + a.z.abs_diff(b.z)
+    }
+    fn encode(&self, coord: VCoord) -> Self::Encoded {
+        for lvl in 0..self.levels {
+            let node = encode(coord, lvl);
+            if self.information.contains_key(&node) {
+                return node;
+            }
+        }
+        encode(coord, 0)
+    }
+    fn leaf(&self, coord: VCoord) -> Self::Encoded {
+        encode(coord, 0)
+    }
+    fn decode(&self, node: Self::Encoded) -> VCoord {
+        VCoord {
+            x: node.x,
+            y: node.y,
+            z: node.z,
+        }
+    }
+    fn initialize(&mut self, source: VCoord, target: VCoord) {
+        // ensure every top-level block between source and target exists
+        let span = 1 << (self.levels - 1);
+        let (min, max) = (
+            encode(
+                VCoord {
+                    x: source.x.min(target.x),
+                    y: source.y.min(target.y),
+                    z: source.z.min(target.z),
+                },
+                self.levels - 1,
+            ),
+            VCoord {
+                x: source.x.max(target.x),
+                y: source.y.max(target.y),
+                z: source.z.max(target.z),
+            },
+        );
+        for x in (min.x..=max.x).step_by(span) {
+            for y in (min.y..=max.y).step_by(span) {
+                for z in (min.z..=max.z).step_by(span) {
+                    self.populate(VCoord { x, y, z });
+                }
+            }
+        }
+    }
+}
+
+impl Octree {
+    pub fn new() -> Self {
+        Self::init(LEVELS)
+    }
+    pub fn init(levels: usize) -> Self {
+        let mut information = HashMap::new();
+        information.insert(
+            encode(VCoord { x: 0, y: 0, z: 0 }, levels - 1),
+            Belief::Unknown,
+        );
+        Self {
+            information,
+            levels,
+        }
+    }
+    /// Inserts the unknown top-level block of a voxel if nothing covers it
+    pub fn populate(&mut self, coord: VCoord) {
+        if self.get_coord(coord).is_none() {
+            self.information
+                .insert(encode(coord, self.levels - 1), Belief::Unknown);
+        }
+    }
+    pub fn get_coord(&self, coord: VCoord) -> Option<(usize, Belief)> {
+        for lvl in 0..self.levels {
+            if let Some(&belief) = self.information.get(&encode(coord, lvl)) {
+                return Some((lvl, belief));
+            }
+        }
+        None
+    }
+    /// Leaf covering the node, when it is at least as coarse as the node
+    pub fn get_node(&self, node: OCoord) -> Option<(OCoord, Belief)> {
+        for lvl in node.l..self.levels {
+            let ancestor = transform(&node, lvl);
+            if let Some(&belief) = self.information.get(&ancestor) {
+                return Some((ancestor, belief));
+            }
+        }
+        None
+    }
+    /// Splits the covering leaf down to the voxel and merges identical octants back up
+    pub fn update_belief(&mut self, coord: VCoord, belief: Belief) {
+        self.populate(coord);
+        let Some((lvl, current)) = self.get_coord(coord) else {
+            return;
+        };
+        if current == belief {
+            return;
+        }
+        let mut node = encode(coord, lvl);
+        while node.l > 0 {
+            self.information.remove(&node);
+            for child in child_hier(&node) {
+                self.information.insert(child, current);
+            }
+            node = encode(coord, node.l - 1);
+        }
+        self.information.insert(node, belief);
+        self.merge(node);
+    }
+    fn merge(&mut self, mut node: OCoord) {
+        // top-level blocks are never merged
+        while node.l + 1 < self.levels {
+            let parent = transform(&node, node.l + 1);
+            let children = child_hier(&parent);
+            let belief = match self.information.get(&children[0]) {
+                Some(&b) => b,
+                None => return,
+            };
+            if !children
+                .iter()
+                .all(|c| self.information.get(c) == Some(&belief))
+            {
+                return;
+            }
+            for child in children {
+                self.information.remove(&child);
+            }
+            self.information.insert(parent, belief);
+            node = parent;
+        }
+    }
+    /// Unoccupied leaves sharing a face with the node
+    fn face_neighbors(&self, node: OCoord) -> Vec<OCoord> {
+        let span = 1 << node.l;
+        let mut neighbors = Vec::new();
+        for d in FACES {
+            let adjacent = OCoord {
+                l: node.l,
+                x: node.x + d.x * span,
+                y: node.y + d.y * span,
+                z: node.z + d.z * span,
+            };
+            // a leaf at least as coarse covers the whole face
+            if let Some((leaf, belief)) = self.get_node(adjacent) {
+                if belief != Belief::Occupied && !neighbors.contains(&leaf) {
+                    neighbors.push(leaf);
+                }
+                continue;
+            }
+            // otherwise finer leaves are found among the octants touching the face
+            let mut stack = vec![adjacent];
+            while let Some(n) = stack.pop() {
+                match self.information.get(&n) {
+                    Some(&belief) if belief != Belief::Occupied => neighbors.push(n),
+                    Some(_) => {}
+                    None if n.l > 0 => {
+                        stack.extend(child_hier(&n).into_iter().filter(|c| facing(c, &n, d)));
+                    }
+                    None => {}
+                }
+            }
+        }
+        neighbors
+    }
+}
+
+impl Default for Octree {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether the octant lies on the side of its parent facing back along d
+fn facing(child: &OCoord, parent: &OCoord, d: VCoord) -> bool {
+    let project = |c: &OCoord| c.x * d.x + c.y * d.y + c.z * d.z;
+    (project(child) == project(parent)) == (d.x + d.y + d.z > 0)
+}
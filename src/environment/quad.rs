@@ -1,10 +1,18 @@
 use crate::global::consts::LEVELS;
-use crate::global::types::{SpatialMap, ACoord, Belief, Bounds, HCoord};
-use crate::hierarchy::encoding::{point, transform, child_hier, encode, grid_hier};
-use crate::hierarchy::proximity::{edge_neighbors};
-use std::collections::HashMap;
+use crate::global::line::bresenham;
+use crate::global::types::plan::CellChange;
+use crate::global::types::{SpatialMap, ACoord, Belief, Bounds, Connectivity, HCoord, Opacity, Region};
+use crate::hierarchy::encoding::{point, transform, child_hier, encode, extent, grid_hier};
+use crate::hierarchy::proximity::{corner_neighbors, edge_leaves, edge_neighbors};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU64, Ordering};
 
 type Information = HashMap<HCoord, QuadNode>;
+
+/// Journal entries kept, older ones are dropped and their readers start over
+const JOURNAL_LIMIT: usize = 1 << 14;
+/// Source of tree identities, every tree made or cloned takes the next one
+static TREES: AtomicU64 = AtomicU64::new(0);
 // Sees in 4 principle components
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
@@ -12,11 +20,66 @@ pub struct QuadNode {
     pub homogenous: bool,
     pub belief: Belief,
 }
-#[derive(Clone)]
+/// Homogeneous node of the quadtree
+///
+/// # Attributes #
+/// * node := hierarchical coordinate of the leaf
+/// * belief := belief shared by every covered cell
+/// * bounds := inclusive rectangle of covered cells
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct QuadLeaf {
+    pub node: HCoord,
+    pub belief: Belief,
+    pub bounds: Bounds,
+}
+
+impl QuadLeaf {
+    fn new(node: HCoord, belief: Belief) -> Self {
+        Self {
+            node,
+            belief,
+            bounds: extent(&node),
+        }
+    }
+}
+
+/// # Attributes #
+/// * information := homogeneous leaves of the tree
+/// * adjacency := leaf -> leaves sharing an edge with it whatever their belief
+/// * balanced := whether edge adjacent leaves are kept within one level of each other
+/// * touched := nodes inserted or removed since the adjacency was last patched
+/// * journal := regions whose leaves changed, oldest first, for graphs derived from the tree
+/// * dropped := journal entries recorded before the oldest one kept
+/// * id := identity of the tree, no other tree or clone shares it
 pub struct QuadTree {
     pub information: Information,
+    pub adjacency: HashMap<HCoord, Vec<HCoord>>,
     pub bounds: Bounds,
     pub levels: usize,
+    pub connectivity: Connectivity,
+    pub balanced: bool,
+    touched: Vec<HCoord>,
+    journal: Vec<Bounds>,
+    dropped: usize,
+    id: u64,
+}
+
+impl Clone for QuadTree {
+    fn clone(&self) -> Self {
+        // a clone edits apart from the original, graphs derived from one never fit the other
+        Self {
+            information: self.information.clone(),
+            adjacency: self.adjacency.clone(),
+            bounds: self.bounds.clone(),
+            levels: self.levels,
+            connectivity: self.connectivity,
+            balanced: self.balanced,
+            touched: self.touched.clone(),
+            journal: self.journal.clone(),
+            dropped: self.dropped,
+            id: TREES.fetch_add(1, Ordering::Relaxed),
+        }
+    }
 }
 
 impl SpatialMap for QuadTree {
@@ -31,7 +94,7 @@ impl SpatialMap for QuadTree {
         HCoord {
             l: 0,
             x: coord.x,
-            y: coord.x,
+            y: coord.y,
         }
     }
     fn decode(&self, node:Self::Encoded) -> ACoord {
@@ -47,6 +110,9 @@ impl SpatialMap for QuadTree {
             y: coord.y,
         }
     }
+    fn covering(&self, node: Self::Encoded) -> Vec<Self::Encoded> {
+        self.range(&extent(&node)).into_iter().map(|leaf| leaf.node).collect()
+    }
     fn initialize(&mut self, _source: ACoord, target: ACoord) {
         // TODO: Needs some sort of intelligent resizing
         // ensure the the grid has been initialized
@@ -76,11 +142,27 @@ impl SpatialMap for QuadTree {
     }
     fn distance(&self, a: Self::Encoded, b: Self::Encoded) -> usize {
         // distance between source-centroid and target-centroid
-        let (a, b) = (point(a), point(b));
-        a.x.abs_diff(b.x) + a.y.abs_diff(b.y)
+        self.connectivity.cost(point(a), point(b))
     }
     fn neighbors(&self, a: Self::Encoded) -> Vec<Self::Encoded> {
-        edge_neighbors(self, a)
+        let mut neighbors = match self.adjacency.get(&a) {
+            // leaves edited since the last relink are stale, the hierarchy is always current
+            Some(adjacent) if adjacent.iter().all(|n| self.information.contains_key(n)) => adjacent
+                .iter()
+                .filter(|n| self.information.get(n).is_some_and(|q| q.belief != Belief::Occupied))
+                .copied()
+                .collect(),
+            // nodes which aren't leaves are resolved through the hierarchy
+            _ => edge_neighbors(self, a),
+        };
+        if self.connectivity != Connectivity::Four {
+            for n in corner_neighbors(self, a) {
+                if !neighbors.contains(&n) {
+                    neighbors.push(n);
+                }
+            }
+        }
+        neighbors
     }
     fn belief(&self, node: Self::Encoded) -> Belief {
         match self.get_node(node) {
@@ -89,21 +171,122 @@ impl SpatialMap for QuadTree {
         }
 
     }
-    fn insert_ray(&mut self, mut pos: ACoord, hit: ACoord) {
+    fn insert_ray(&mut self, pos: ACoord, hit: ACoord) -> Vec<CellChange> {
         // beliefs not recorded are assumed unknown
-        // handles simulation compass rose signals
-        let (dy, dx) = (hit.y - pos.y, hit.x - pos.x);
-        let (del_y, del_x) = (dy.signum(), dx.signum());
-        pos.x += del_x;
-        pos.y += del_y;
-        while pos != hit {
-            self.update_belief(&pos, Belief::Free);
-            pos.x += del_x;
-            pos.y += del_y;
+        // walks the straight line to the hit, diagonally where it slants
+        let mut changes = Vec::new();
+        for cell in bresenham(pos, hit).into_iter().skip(1).take_while(|&c| c != hit) {
+            let old = self.belief(self.encode(cell));
+            self.update_belief(&cell, Belief::Free);
+            changes.extend(CellChange::between(cell, old, Belief::Free));
         }
+        let old = self.belief(self.encode(hit));
         self.update_belief(&hit, Belief::Occupied);
+        changes.extend(CellChange::between(hit, old, Belief::Occupied));
+        changes
+    }
+    fn regions(&self) -> Vec<Region> {
+        self.leaves()
+            .map(|leaf| Region {
+                bounds: leaf.bounds,
+                belief: leaf.belief,
+            })
+            .collect()
+    }
+    fn fill_region(&mut self, rect: &Bounds, belief: Belief) {
+        self.fill_rect(rect, belief);
+    }
+    fn line_of_sight(&self, a: ACoord, b: ACoord, unknown: Opacity) -> bool {
+        // the supercover of the segment, skipping across each leaf in one step
+        let (dx, dy) = (b.x.abs_diff(a.x), b.y.abs_diff(a.y));
+        let (sx, sy) = ((b.x - a.x).signum(), (b.y - a.y).signum());
+        let (mut nx, mut ny) = (0, 0);
+        let mut curr = a;
+        loop {
+            let (leaf, belief) = self.leaf_at(curr);
+            if belief.blocks(unknown) {
+                return false;
+            }
+            if nx == dx && ny == dy {
+                return true;
+            }
+            // crossings are ordered by (1 + 2 i) * dy for borders along x, (1 + 2 j) * dx along y
+            let within = |c: isize, min: isize, max: isize, s: isize| match s {
+                1 => (max - c) as usize,
+                -1 => (c - min) as usize,
+                _ => 0,
+            };
+            let exit_x = nx + within(curr.x, leaf.min_x, leaf.max_x, sx);
+            let exit_y = ny + within(curr.y, leaf.min_y, leaf.max_y, sy);
+            let at_x = (exit_x < dx).then(|| (1 + 2 * exit_x) * dy);
+            let at_y = (exit_y < dy).then(|| (1 + 2 * exit_y) * dx);
+            let Some(exit) = at_x.into_iter().chain(at_y).min() else {
+                // the segment ends inside the leaf
+                return true;
+            };
+            // borders strictly before the exit lie inside the leaf
+            let before =
+                |spacing: usize, count: usize| ((exit + spacing - 1) / (2 * spacing)).min(count);
+            (nx, ny) = match (at_x == Some(exit), at_y == Some(exit)) {
+                (true, false) => (exit_x, before(dx, dy)),
+                (false, true) => (before(dy, dx), exit_y),
+                _ => (exit_x, exit_y),
+            };
+            curr = ACoord {
+                x: a.x + sx * nx as isize,
+                y: a.y + sy * ny as isize,
+            };
+            let (decision, threshold) = ((1 + 2 * nx) * dy, (1 + 2 * ny) * dx);
+            if decision == threshold {
+                // through a corner both cells beside it are touched
+                let sides = [ACoord { x: curr.x + sx, ..curr }, ACoord { y: curr.y + sy, ..curr }];
+                if sides.iter().any(|&c| self.leaf_at(c).1.blocks(unknown)) {
+                    return false;
+                }
+                (nx, ny) = (nx + 1, ny + 1);
+            } else if decision < threshold {
+                nx += 1;
+            } else {
+                ny += 1;
+            }
+            curr = ACoord {
+                x: a.x + sx * nx as isize,
+                y: a.y + sy * ny as isize,
+            };
+        }
+    }
+    fn raycast(
+        &self,
+        origin: ACoord,
+        direction: ACoord,
+        max_range: usize,
+        unknown: Opacity,
+    ) -> Option<ACoord> {
+        let mut n_xy = origin;
+        let mut remaining = max_range;
+        while remaining > 0 {
+            n_xy.x += direction.x;
+            n_xy.y += direction.y;
+            remaining -= 1;
+            let (leaf, belief) = self.leaf_at(n_xy);
+            if belief.blocks(unknown) {
+                return Some(n_xy);
+            }
+            // the steps still landing inside the leaf see the same belief
+            let within = |c: isize, min: isize, max: isize, d: isize| match d.signum() {
+                1 => ((max - c) / d) as usize,
+                -1 => ((c - min) / -d) as usize,
+                _ => usize::MAX,
+            };
+            let skip = within(n_xy.x, leaf.min_x, leaf.max_x, direction.x)
+                .min(within(n_xy.y, leaf.min_y, leaf.max_y, direction.y))
+                .min(remaining);
+            n_xy.x += direction.x * skip as isize;
+            n_xy.y += direction.y * skip as isize;
+            remaining -= skip;
+        }
+        None
     }
-
 }
 
 
@@ -112,28 +295,44 @@ impl QuadTree {
         Self::init(LEVELS)
     }
     pub fn init(levels: usize) -> Self {
-        let mut information = HashMap::new();
         // level 0 contains no shift and level is inclusive
         let stride = 1 << (levels - 1);
-        information.insert(
-            encode(ACoord { x: 0, y: 0 }, levels - 1),
-            QuadNode {
-                belief: Belief::Unknown,
-                homogenous: true,
-            },
-        );
         let bounds = Bounds {
             min_x: 0,
             min_y: 0,
             max_x: stride - 1,
             max_y: stride - 1,
         };
+        let mut quad = Self::empty(levels, bounds);
+        quad.attach(
+            encode(ACoord { x: 0, y: 0 }, levels - 1),
+            QuadNode {
+                belief: Belief::Unknown,
+                homogenous: true,
+            },
+        );
+        quad.relink();
+        quad
+    }
+    /// Quadtree without any nodes
+    pub(crate) fn empty(levels: usize, bounds: Bounds) -> Self {
         Self {
-            information,
+            information: HashMap::new(),
+            adjacency: HashMap::new(),
             bounds,
-            levels: levels,
+            levels,
+            connectivity: Connectivity::Four,
+            balanced: false,
+            touched: Vec::new(),
+            journal: Vec::new(),
+            dropped: 0,
+            id: TREES.fetch_add(1, Ordering::Relaxed),
         }
     }
+    pub fn with_connectivity(mut self, connectivity: Connectivity) -> Self {
+        self.connectivity = connectivity;
+        self
+    }
 }
 
 impl QuadTree {
@@ -148,13 +347,15 @@ impl QuadTree {
         self.bounds.min_y = self.bounds.min_y.min(node.y);
         self.bounds.max_x = self.bounds.max_x.max(node.x + span);
         self.bounds.max_y = self.bounds.max_y.max(node.y + span);
-        self.information.insert(
+        self.attach(
             node,
             QuadNode {
                 homogenous: true,
                 belief: Belief::Unknown,
             },
         );
+        self.relink();
+        self.rebalance(vec![node]);
     }
     fn update_bounds(&mut self, coord: ACoord) {
         if self.get_coord(coord).is_some() {
@@ -166,7 +367,7 @@ impl QuadTree {
         self.bounds.min_y = self.bounds.min_y.min(node.y);
         self.bounds.max_x = self.bounds.max_x.max(node.x + span);
         self.bounds.max_y = self.bounds.max_y.max(node.y + span);
-        self.information.insert(
+        self.attach(
             node,
             QuadNode {
                 homogenous: true,
@@ -189,8 +390,9 @@ impl QuadTree {
             if let Some(ancestor) = self.information.get_mut(&m_coord) {
                 ancestor.homogenous = true;
                 ancestor.belief = belief;
+                self.touched.push(m_coord);
             } else {
-                self.information.insert(
+                self.attach(
                     m_coord,
                     QuadNode {
                         homogenous: true,
@@ -214,7 +416,7 @@ impl QuadTree {
             }
         }
         while let Some((lvl, m)) = stack.pop() {
-            self.information.remove(&m);
+            self.detach(&m);
             if lvl > 0 {
                 for g in child_hier(&m) {
                     stack.push((lvl - 1, g));
@@ -246,16 +448,25 @@ impl QuadTree {
         false
     }
     pub fn update_belief(&mut self, coord: &ACoord, belief: Belief) {
+        // leaves may change belief in place without being touched
+        self.record(Bounds {
+            min_x: coord.x,
+            min_y: coord.y,
+            max_x: coord.x,
+            max_y: coord.y,
+        });
         self.update_bounds(*coord);
-        if self.insert_known(*coord, belief) {
-            return;
+        if !self.insert_known(*coord, belief) {
+            self.insert_unknown(*coord, belief);
         }
-        self.insert_unknown(*coord, belief);
+        self.relink();
+        self.rebalance(vec![encode(*coord, self.levels - 1)]);
+        debug_assert_eq!(self.inspect([encode(*coord, self.levels - 1)]), vec![]);
     }
-    fn split_cell(&mut self, node: HCoord) {
-        if let Some(ancestor) = self.information.remove(&node) {
+    pub(crate) fn split_cell(&mut self, node: HCoord) {
+        if let Some(ancestor) = self.detach(&node) {
             for g in child_hier(&node) {
-                self.information.insert(
+                self.attach(
                     g,
                     QuadNode {
                         belief: ancestor.belief,
@@ -274,7 +485,7 @@ impl QuadTree {
                 self.split_cell(encode(*coord, lvl));
             }
         }
-        self.information.insert(
+        self.attach(
             HCoord {
                 l: 0,
                 x: coord.x,
@@ -308,6 +519,13 @@ impl QuadTree {
         }
         None
     }
+    /// Extent and belief of the leaf holding a cell, cells outside the tree stand alone
+    fn leaf_at(&self, coord: ACoord) -> (Bounds, Belief) {
+        match self.get_coord(coord) {
+            Some((lvl, belief)) => (extent(&encode(coord, lvl)), belief),
+            None => (extent(&encode(coord, 0)), Belief::Unknown),
+        }
+    }
     pub fn get_coord(&self, coord: ACoord) -> Option<(usize, Belief)> {
         for lvl in 0..self.levels {
             let node = encode(coord, lvl);
@@ -320,3 +538,211 @@ impl QuadTree {
         None
     }
 }
+
+impl QuadTree {
+    /// Homogeneous leaves in arbitrary order
+    pub fn leaves(&self) -> impl Iterator<Item = QuadLeaf> + '_ {
+        self.information
+            .iter()
+            .filter(|(_, n)| n.homogenous)
+            .map(|(&node, n)| QuadLeaf::new(node, n.belief))
+    }
+    /// Leaves intersecting the inclusive rectangle
+    pub fn range(&self, rect: &Bounds) -> Vec<QuadLeaf> {
+        let mut found = Vec::new();
+        let mut stack = self.blocks(rect);
+        while let Some(node) = stack.pop() {
+            match self.information.get(&node) {
+                Some(n) if n.homogenous => found.push(QuadLeaf::new(node, n.belief)),
+                _ if node.l > 0 => stack.extend(
+                    child_hier(&node)
+                        .into_iter()
+                        .filter(|c| extent(c).intersects(rect)),
+                ),
+                _ => {}
+            }
+        }
+        found
+    }
+    /// Sets the belief of every cell in the inclusive rectangle
+    ///
+    /// Covered nodes are replaced whole rather than cell by cell and
+    /// siblings are merged on the way back up.
+    pub fn fill_rect(&mut self, rect: &Bounds, belief: Belief) {
+        self.record(rect.clone());
+        for block in self.blocks(rect) {
+            // regions never observed are opened as unknown like update_bounds
+            self.update_bounds(ACoord {
+                x: block.x,
+                y: block.y,
+            });
+            self.fill_node(block, rect, belief);
+        }
+        self.relink();
+        self.rebalance(self.blocks(rect));
+        debug_assert_eq!(self.inspect(self.blocks(rect)), vec![]);
+    }
+    /// Top level nodes intersecting the inclusive rectangle
+    pub(crate) fn blocks(&self, rect: &Bounds) -> Vec<HCoord> {
+        let top = self.levels - 1;
+        let span = 1 << top;
+        let (min, max) = (
+            encode(ACoord { x: rect.min_x, y: rect.min_y }, top),
+            encode(ACoord { x: rect.max_x, y: rect.max_y }, top),
+        );
+        let mut blocks = Vec::new();
+        for x in (min.x..=max.x).step_by(span) {
+            for y in (min.y..=max.y).step_by(span) {
+                blocks.push(HCoord { l: top, x, y });
+            }
+        }
+        blocks
+    }
+    fn fill_node(&mut self, node: HCoord, rect: &Bounds, belief: Belief) {
+        if rect.encloses(&extent(&node)) {
+            self.prune(node);
+            self.attach(
+                node,
+                QuadNode {
+                    homogenous: true,
+                    belief,
+                },
+            );
+            return;
+        }
+        if let Some(n) = self.information.get(&node) {
+            if n.belief == belief {
+                return;
+            }
+            self.split_cell(node);
+        }
+        for child in child_hier(&node) {
+            if extent(&child).intersects(rect) {
+                self.fill_node(child, rect, belief);
+            }
+        }
+        self.merge(node);
+    }
+    /// Removes every descendant of the node
+    fn prune(&mut self, node: HCoord) {
+        let mut stack = vec![node];
+        while let Some(m) = stack.pop() {
+            if m.l == 0 {
+                continue;
+            }
+            for g in child_hier(&m) {
+                if self.detach(&g).is_none() {
+                    stack.push(g);
+                }
+            }
+        }
+    }
+    /// Replaces four homogeneous children sharing a belief with their parent
+    pub(crate) fn merge(&mut self, node: HCoord) {
+        let children = child_hier(&node);
+        let mut shared = None;
+        for g in &children {
+            match (self.information.get(g), shared) {
+                (Some(n), None) if n.homogenous => shared = Some(n.belief),
+                (Some(n), Some(belief)) if n.homogenous && n.belief == belief => {}
+                _ => return,
+            }
+        }
+        if let Some(belief) = shared {
+            for g in &children {
+                self.detach(g);
+            }
+            self.attach(
+                node,
+                QuadNode {
+                    homogenous: true,
+                    belief,
+                },
+            );
+        }
+    }
+}
+
+impl QuadTree {
+    /// Inserts a node, its adjacency is patched by the next relink
+    pub(crate) fn attach(&mut self, node: HCoord, qnode: QuadNode) {
+        self.information.insert(node, qnode);
+        self.touched.push(node);
+    }
+    /// Removes a node, its adjacency is patched by the next relink
+    fn detach(&mut self, node: &HCoord) -> Option<QuadNode> {
+        let removed = self.information.remove(node);
+        if removed.is_some() {
+            self.touched.push(*node);
+        }
+        removed
+    }
+    /// Identity of the tree, trees made or cloned apart never share it
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+    /// Number of journal entries recorded since the tree was made
+    pub fn generation(&self) -> usize {
+        self.dropped + self.journal.len()
+    }
+    /// Regions whose leaves changed since the generation, oldest first
+    ///
+    /// Graphs derived from the tree remember its id and the generation they
+    /// have seen and patch the regions recorded after it. None once the
+    /// entries have been dropped, the graph is then rebuilt.
+    pub fn journal_since(&self, generation: usize) -> Option<&[Bounds]> {
+        let start = generation.checked_sub(self.dropped)?;
+        self.journal.get(start..)
+    }
+    fn record(&mut self, rect: Bounds) {
+        if self.journal.len() >= JOURNAL_LIMIT {
+            // the older half goes, readers behind it rebuild
+            let half = self.journal.len() / 2;
+            self.journal.drain(..half);
+            self.dropped += half;
+        }
+        self.journal.push(rect);
+    }
+    /// Patches the adjacency around every node inserted or removed since the last relink
+    ///
+    /// Mutations keep the adjacency current, information edited directly needs
+    /// its nodes relinked through relink_nodes.
+    pub fn relink(&mut self) {
+        let touched = std::mem::take(&mut self.touched);
+        self.relink_nodes(touched);
+    }
+    pub fn relink_nodes(&mut self, nodes: Vec<HCoord>) {
+        let mut seen = HashSet::new();
+        let nodes: Vec<HCoord> = nodes.into_iter().filter(|n| seen.insert(*n)).collect();
+        for node in &nodes {
+            self.record(extent(node));
+        }
+        for node in &nodes {
+            if let Some(adjacent) = self.adjacency.remove(node) {
+                for n in adjacent {
+                    if let Some(links) = self.adjacency.get_mut(&n) {
+                        links.retain(|m| m != node);
+                    }
+                }
+            }
+        }
+        for &node in &nodes {
+            if !self.information.contains_key(&node) {
+                continue;
+            }
+            let adjacent = edge_leaves(self, node);
+            for &n in &adjacent {
+                let links = self.adjacency.entry(n).or_default();
+                if !links.contains(&node) {
+                    links.push(node);
+                }
+            }
+            let links = self.adjacency.entry(node).or_default();
+            for n in adjacent {
+                if !links.contains(&n) {
+                    links.push(n);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use crate::environment::quad::QuadTree;
+use crate::global::types::{ACoord, HCoord};
+use crate::hierarchy::encoding::{child_hier, extent, transform};
+use crate::hierarchy::proximity::edge_leaves;
+use std::collections::HashSet;
+
+// A 2:1 balanced tree is the coarsest refinement of the compressed tree where
+// edge adjacent leaves differ by at most one level. A change never forces splits
+// further than the top level blocks around it, so only those are rebuilt.
+
+impl QuadTree {
+    /// Keeps edge adjacent leaves within one level of each other from now on
+    pub fn with_balance(mut self) -> Self {
+        self.balanced = true;
+        let blocks: HashSet<HCoord> = self
+            .information
+            .keys()
+            .map(|n| transform(n, self.levels - 1))
+            .collect();
+        self.rebalance(blocks.into_iter().collect());
+        self
+    }
+    /// Coarsens the blocks and those around them, then splits until balanced again
+    pub(crate) fn rebalance(&mut self, blocks: Vec<HCoord>) {
+        if !self.balanced {
+            return;
+        }
+        let span = 1 << (self.levels - 1);
+        let mut region = HashSet::new();
+        for block in blocks {
+            for dx in [-span, 0, span] {
+                for dy in [-span, 0, span] {
+                    let around = HCoord {
+                        l: block.l,
+                        x: block.x + dx,
+                        y: block.y + dy,
+                    };
+                    // blocks which were never opened hold no leaves
+                    if self.get_coord(ACoord { x: around.x, y: around.y }).is_some() {
+                        region.insert(around);
+                    }
+                }
+            }
+        }
+        let mut queue = Vec::new();
+        for &block in &region {
+            self.coarsen(block);
+            queue.extend(self.range(&extent(&block)).into_iter().map(|leaf| leaf.node));
+        }
+        while let Some(node) = queue.pop() {
+            if !self.information.contains_key(&node) {
+                continue;
+            }
+            for n in edge_leaves(self, node) {
+                // the coarser of the pair is split, its children are checked again
+                let coarse = if n.l > node.l + 1 {
+                    n
+                } else if node.l > n.l + 1 {
+                    node
+                } else {
+                    continue;
+                };
+                self.split_cell(coarse);
+                queue.extend(child_hier(&coarse));
+                queue.push(if coarse == node { n } else { node });
+                if coarse == node {
+                    break;
+                }
+            }
+        }
+        self.relink();
+    }
+    /// Merges every group of sibling leaves sharing a belief, finest first
+    fn coarsen(&mut self, block: HCoord) {
+        let mut parents = vec![HashSet::new(); block.l + 1];
+        for leaf in self.range(&extent(&block)) {
+            if leaf.node.l < block.l {
+                parents[leaf.node.l + 1].insert(transform(&leaf.node, leaf.node.l + 1));
+            }
+        }
+        for lvl in 1..=block.l {
+            for parent in std::mem::take(&mut parents[lvl]) {
+                self.merge(parent);
+                if lvl < block.l && self.information.contains_key(&parent) {
+                    parents[lvl + 1].insert(transform(&parent, lvl + 1));
+                }
+            }
+        }
+    }
+}
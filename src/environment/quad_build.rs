@@ -0,0 +1,103 @@
+use crate::environment::grid::Grid;
+use crate::environment::quad::{QuadNode, QuadTree};
+use crate::global::types::{ACoord, Belief, Bounds, HCoord, SpatialMap};
+use crate::hierarchy::encoding::{child_hier, encode};
+
+impl QuadTree {
+    /// Builds a compressed quadtree bottom-up from a dense belief source
+    ///
+    /// Cells inside the inclusive bounds take the belief of the source, the
+    /// remainder of the covering top level nodes is unknown. Produces the same
+    /// information as inserting every cell through update_belief.
+    pub fn from_fn<F>(levels: usize, bounds: &Bounds, belief: F) -> Self
+    where
+        F: Fn(ACoord) -> Belief,
+    {
+        let top = levels - 1;
+        let span: isize = 1 << top;
+        let mut quad = Self::empty(levels, bounds.clone());
+        let (min, max) = (
+            encode(ACoord { x: bounds.min_x, y: bounds.min_y }, top),
+            encode(ACoord { x: bounds.max_x, y: bounds.max_y }, top),
+        );
+        for x in (min.x..=max.x).step_by(span as usize) {
+            for y in (min.y..=max.y).step_by(span as usize) {
+                let block = HCoord { l: top, x, y };
+                quad.bounds.min_x = quad.bounds.min_x.min(x);
+                quad.bounds.min_y = quad.bounds.min_y.min(y);
+                quad.bounds.max_x = quad.bounds.max_x.max(x + span);
+                quad.bounds.max_y = quad.bounds.max_y.max(y + span);
+                if let Some(b) = quad.build(block, bounds, &belief) {
+                    quad.insert_leaf(block, b);
+                }
+            }
+        }
+        quad.relink();
+        quad
+    }
+    /// Compressed quadtree of a grid, unrecorded cells within its bounds are free
+    pub fn from_grid(grid: &Grid, levels: usize) -> Self {
+        Self::from_fn(levels, &grid.bounds, |coord| {
+            SpatialMap::belief(grid, coord)
+        })
+        .with_connectivity(grid.connectivity)
+    }
+    /// Dense grid recording the belief of every covered cell
+    pub fn to_grid(&self) -> Grid {
+        let mut grid = Grid::new().with_connectivity(self.connectivity);
+        for leaf in self.leaves() {
+            for x in leaf.bounds.min_x..=leaf.bounds.max_x {
+                for y in leaf.bounds.min_y..=leaf.bounds.max_y {
+                    grid.information.insert(ACoord { x, y }, leaf.belief);
+                }
+            }
+            grid.update_bounds(ACoord {
+                x: leaf.bounds.min_x,
+                y: leaf.bounds.min_y,
+            });
+            grid.update_bounds(ACoord {
+                x: leaf.bounds.max_x,
+                y: leaf.bounds.max_y,
+            });
+        }
+        grid
+    }
+    /// Belief of a homogeneous node, otherwise its homogeneous children are stored
+    fn build<F>(&mut self, node: HCoord, bounds: &Bounds, belief: &F) -> Option<Belief>
+    where
+        F: Fn(ACoord) -> Belief,
+    {
+        if node.l == 0 {
+            let coord = ACoord {
+                x: node.x,
+                y: node.y,
+            };
+            if bounds.contains(coord) {
+                return Some(belief(coord));
+            }
+            return Some(Belief::Unknown);
+        }
+        let children = child_hier(&node);
+        let beliefs = children.map(|c| self.build(c, bounds, belief));
+        if let Some(b) = beliefs[0]
+            && beliefs.iter().all(|&c| c == Some(b))
+        {
+            return Some(b);
+        }
+        for (child, b) in children.into_iter().zip(beliefs) {
+            if let Some(b) = b {
+                self.insert_leaf(child, b);
+            }
+        }
+        None
+    }
+    fn insert_leaf(&mut self, node: HCoord, belief: Belief) {
+        self.attach(
+            node,
+            QuadNode {
+                homogenous: true,
+                belief,
+            },
+        );
+    }
+}
@@ -0,0 +1,180 @@
+use crate::environment::quad::{QuadNode, QuadTree};
+use crate::global::types::HCoord;
+use crate::hierarchy::encoding::{child_hier, extent, transform};
+use crate::hierarchy::proximity::edge_leaves;
+use std::collections::{HashMap, HashSet};
+use std::mem::size_of;
+
+/// Broken structural invariant of the quadtree
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Violation {
+    /// node is not aligned to its level or lies above the top level
+    Misplaced { node: HCoord },
+    /// stored node which is not homogeneous
+    Heterogeneous { node: HCoord },
+    /// leaf stored below another leaf, an orphaned descendant
+    Overlap { node: HCoord, ancestor: HCoord },
+    /// region of an opened top level block no leaf covers
+    Gap { node: HCoord },
+    /// four sibling leaves sharing a belief which were never merged
+    Unmerged { parent: HCoord },
+    /// leaf more than one level coarser than an edge adjacent leaf of a balanced tree
+    Unbalanced { node: HCoord, neighbor: HCoord },
+    /// recorded adjacency of a leaf disagrees with its edge leaves
+    Adjacency { node: HCoord },
+}
+
+/// Structural statistics of the quadtree
+///
+/// # Attributes #
+/// * per_level := number of leaves at each level
+/// * leaves := number of leaves
+/// * cells := number of cells covered by the leaves
+/// * compression := cells per leaf
+/// * memory := approximate heap bytes held by the leaves and their adjacency
+#[derive(PartialEq, Clone, Debug)]
+pub struct QuadStats {
+    pub per_level: Vec<usize>,
+    pub leaves: usize,
+    pub cells: usize,
+    pub compression: f64,
+    pub memory: usize,
+}
+
+impl QuadTree {
+    /// Checks every structural invariant, the statistics are returned when none is broken
+    pub fn validate(&self) -> Result<QuadStats, Vec<Violation>> {
+        let blocks: HashSet<HCoord> = self
+            .information
+            .keys()
+            .filter(|n| n.l < self.levels)
+            .map(|n| transform(n, self.levels - 1))
+            .collect();
+        let mut violations = self.inspect(blocks);
+        for &node in self.information.keys() {
+            if node.l >= self.levels {
+                violations.push(Violation::Misplaced { node });
+                continue;
+            }
+            // nodes off the hierarchy or below a leaf are never reached walking down
+            let span = 1 << node.l;
+            if node.x.rem_euclid(span) != 0 || node.y.rem_euclid(span) != 0 {
+                violations.push(Violation::Misplaced { node });
+            }
+            let ancestor = (node.l + 1..self.levels)
+                .map(|lvl| transform(&node, lvl))
+                .find(|a| self.information.contains_key(a));
+            if let Some(ancestor) = ancestor {
+                violations.push(Violation::Overlap { node, ancestor });
+            }
+        }
+        // links recorded for nodes which no longer exist
+        for node in self.adjacency.keys() {
+            if !self.information.contains_key(node) {
+                violations.push(Violation::Adjacency { node: *node });
+            }
+        }
+        if violations.is_empty() {
+            Ok(self.stats())
+        } else {
+            Err(violations)
+        }
+    }
+    /// Invariants broken within the top level blocks
+    ///
+    /// Only the leaves of the blocks are visited, walking down the hierarchy
+    /// from each block, so checking an edit costs the size of its blocks.
+    pub(crate) fn inspect(&self, blocks: impl IntoIterator<Item = HCoord>) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for block in blocks {
+            let mut stored = HashMap::new();
+            if !self.gather(block, &mut stored, &mut violations) {
+                violations.push(Violation::Gap { node: block });
+            }
+            for (&node, qnode) in &stored {
+                self.inspect_node(node, qnode, &mut violations);
+            }
+        }
+        violations
+    }
+    /// Collects the leaves under a node, false when none covers any of it
+    ///
+    /// Uncovered children of a covered node are gaps, siblings sharing a
+    /// belief are unmerged unless balance forces their split.
+    fn gather(
+        &self,
+        node: HCoord,
+        stored: &mut HashMap<HCoord, QuadNode>,
+        violations: &mut Vec<Violation>,
+    ) -> bool {
+        if let Some(&qnode) = self.information.get(&node) {
+            stored.insert(node, qnode);
+            return true;
+        }
+        if node.l == 0 {
+            return false;
+        }
+        let children = child_hier(&node);
+        let covered: Vec<bool> = children
+            .iter()
+            .map(|&c| self.gather(c, stored, violations))
+            .collect();
+        if !covered.contains(&true) {
+            // the caller reports the highest uncovered node
+            return false;
+        }
+        for (&child, _) in children.iter().zip(&covered).filter(|(_, c)| !**c) {
+            violations.push(Violation::Gap { node: child });
+        }
+        let beliefs: Vec<_> = children.iter().map(|c| stored.get(c).map(|n| n.belief)).collect();
+        if beliefs[0].is_some() && beliefs.iter().all(|&b| b == beliefs[0]) {
+            // balancing splits a leaf only beside a leaf two levels finer
+            let forced = self.balanced && edge_leaves(self, node).iter().any(|n| n.l + 2 <= node.l);
+            if !forced {
+                violations.push(Violation::Unmerged { parent: node });
+            }
+        }
+        true
+    }
+    fn inspect_node(&self, node: HCoord, qnode: &QuadNode, violations: &mut Vec<Violation>) {
+        if !qnode.homogenous {
+            violations.push(Violation::Heterogeneous { node });
+        }
+        let mut expected = edge_leaves(self, node);
+        if self.balanced
+            && let Some(&neighbor) = expected.iter().find(|n| node.l > n.l + 1)
+        {
+            violations.push(Violation::Unbalanced { node, neighbor });
+        }
+        let mut recorded = self.adjacency.get(&node).cloned().unwrap_or_default();
+        expected.sort_by_key(|n| (n.l, n.x, n.y));
+        recorded.sort_by_key(|n| (n.l, n.x, n.y));
+        if expected != recorded {
+            violations.push(Violation::Adjacency { node });
+        }
+    }
+    /// Leaves per level, covered cells, compression and approximate memory
+    pub fn stats(&self) -> QuadStats {
+        let mut per_level = vec![0; self.levels];
+        let mut cells = 0;
+        for leaf in self.leaves() {
+            if let Some(count) = per_level.get_mut(leaf.node.l) {
+                *count += 1;
+            }
+            let bounds = extent(&leaf.node);
+            cells += ((bounds.max_x - bounds.min_x + 1) * (bounds.max_y - bounds.min_y + 1)) as usize;
+        }
+        let leaves: usize = per_level.iter().sum();
+        let links: usize = self.adjacency.values().map(|l| l.capacity()).sum();
+        let memory = self.information.capacity() * size_of::<(HCoord, QuadNode)>()
+            + self.adjacency.capacity() * size_of::<(HCoord, Vec<HCoord>)>()
+            + links * size_of::<HCoord>();
+        QuadStats {
+            per_level,
+            leaves,
+            cells,
+            compression: if leaves == 0 { 0.0 } else { cells as f64 / leaves as f64 },
+            memory,
+        }
+    }
+}
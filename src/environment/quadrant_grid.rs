@@ -1,4 +1,6 @@
-use crate::global::types::{ACoord, Belief, SpatialMap};
+use crate::global::line::bresenham;
+use crate::global::types::plan::CellChange;
+use crate::global::types::{ACoord, Belief, Bounds, CARDINALS, Region, SpatialMap};
 
 /// Ordering reflects quadrants in standard euclidean
 ///
@@ -7,9 +9,9 @@ use crate::global::types::{ACoord, Belief, SpatialMap};
 /// Q3 :: x <= 0, y <= 0;
 /// Q3 :: x >  0, y <  0;
 ///
-/// quadrants organized by x, y
+/// quadrants organized by x, y, slots padded but never written hold none
 pub struct QuadrantGrid {
-    q: [Vec<Vec<Belief>>; 4],
+    q: [Vec<Vec<Option<Belief>>>; 4],
 }
 /// Point for the quadrant
 ///
@@ -38,10 +40,9 @@ impl SpatialMap for QuadrantGrid {
     fn initialize(&mut self, _source: ACoord, _target: ACoord) {}
     fn belief(&self, node: Self::Encoded) -> Belief {
         let store = self.transform(node);
-        if self.q[store.q].len() <= store.x || self.q[store.q][store.x].len() <= store.y {
-            Belief::Unknown
-        } else {
-            self.q[store.q][store.x][store.y]
+        match self.q[store.q].get(store.x).and_then(|column| column.get(store.y)) {
+            Some(&Some(belief)) => belief,
+            _ => Belief::Unknown,
         }
     }
     fn distance(&self, a: Self::Encoded, b: Self::Encoded) -> usize {
@@ -54,38 +55,62 @@ impl SpatialMap for QuadrantGrid {
         }
     }
     fn neighbors(&self, node: Self::Encoded) -> Vec<Self::Encoded> {
-        vec![
-            Self::Encoded {
-                x: node.x + 1,
-                y: node.y,
-            },
-            Self::Encoded {
-                x: node.x,
-                y: node.y + 1,
-            },
-            Self::Encoded {
-                x: node.x - 1,
-                y: node.y,
-            },
-            Self::Encoded {
-                x: node.x,
-                y: node.y - 1,
-            },
-        ]
+        let mut valid = Vec::new();
+        for d in CARDINALS {
+            let n_xy = ACoord {
+                x: node.x + d.x,
+                y: node.y + d.y,
+            };
+            if self.belief(n_xy) != Belief::Occupied {
+                valid.push(n_xy);
+            }
+        }
+        valid
     }
-    fn insert_ray(&mut self, mut pos: ACoord, hit: ACoord) {
+    fn insert_ray(&mut self, pos: ACoord, hit: ACoord) -> Vec<CellChange> {
         // beliefs not recorded are assumed unknown
-        // handles simulation compass rose signals
-        let (dy, dx) = (hit.y - pos.y, hit.x - pos.x);
-        let (del_y, del_x) = (dy.signum(), dx.signum());
-        pos.x += del_x;
-        pos.y += del_y;
-        while pos != hit {
-            self.update_belief(hit, Belief::Free);
-            pos.x += del_x;
-            pos.y += del_y;
+        // walks the straight line to the hit, diagonally where it slants
+        let mut changes = Vec::new();
+        for cell in bresenham(pos, hit).into_iter().skip(1).take_while(|&c| c != hit) {
+            let old = self.belief(self.encode(cell));
+            self.update_belief(cell, Belief::Free);
+            changes.extend(CellChange::between(cell, old, Belief::Free));
         }
+        let old = self.belief(self.encode(hit));
         self.update_belief(hit, Belief::Occupied);
+        changes.extend(CellChange::between(hit, old, Belief::Occupied));
+        changes
+    }
+    fn regions(&self) -> Vec<Region> {
+        let mut regions = Vec::new();
+        for (q, quadrant) in self.q.iter().enumerate() {
+            for (x, column) in quadrant.iter().enumerate() {
+                for (y, &slot) in column.iter().enumerate() {
+                    // padding, including the slot of quadrant 2 at the origin quadrant 0 holds
+                    let Some(belief) = slot else {
+                        continue;
+                    };
+                    let coord = self.untransform(QPoint { q, x, y });
+                    regions.push(Region {
+                        bounds: Bounds {
+                            min_x: coord.x,
+                            min_y: coord.y,
+                            max_x: coord.x,
+                            max_y: coord.y,
+                        },
+                        belief,
+                    });
+                }
+            }
+        }
+        regions
+    }
+    fn fill_region(&mut self, rect: &Bounds, belief: Belief) {
+        for x in rect.min_x..=rect.max_x {
+            for y in rect.min_y..=rect.max_y {
+                self.update_belief(ACoord { x, y }, belief);
+            }
+        }
     }
 }
 
@@ -102,23 +127,24 @@ impl QuadrantGrid {
     }
     fn update_belief(&mut self, coord: ACoord, belief: Belief) {
         let s = self.transform(coord);
-        let m = self.q[s.q].len();
-        for _ in s.x..=m {
+        while self.q[s.q].len() <= s.x {
             self.q[s.q].push(Vec::new());
         }
-        let n = self.q[s.q][s.x].len();
-        for _ in s.y..=n {
-            self.q[s.q][s.x].push(Belief::Unknown);
+        while self.q[s.q][s.x].len() <= s.y {
+            self.q[s.q][s.x].push(None);
         }
-        self.q[s.q][s.x][s.y] = belief;
+        self.q[s.q][s.x][s.y] = Some(belief);
     }
     pub fn transform(&self, coord: ACoord) -> Storage {
         let (mut x, mut y) = (coord.x.abs(), coord.y.abs());
-        let q = match (coord.x >= 0, coord.y >= 0) {
-            (true, true) => 0,
-            (false, true) => 1,
-            (false, false) => 2,
-            (true, false) => 3,
+        let q = if coord.x >= 0 && coord.y >= 0 {
+            0
+        } else if coord.x < 0 && coord.y > 0 {
+            1
+        } else if coord.x <= 0 && coord.y <= 0 {
+            2
+        } else {
+            3
         };
         if q == 1 || q == 3 {
             (x, y) = (x - 1, y - 1);
@@ -1,2 +1,4 @@
 pub mod consts;
+pub mod line;
+pub mod rng;
 pub mod types;
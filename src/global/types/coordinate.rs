@@ -25,9 +25,67 @@ pub const COMPASS_ROSE: [ACoord; 8] = [
     ACoord { x: 1, y: -1 },
 ];
 
+/// Axial hex directions counter-clockwise, x := q and y := r
+pub const HEX_DIRECTIONS: [ACoord; 6] = [
+    ACoord { x: 1, y: 0 },
+    ACoord { x: 1, y: -1 },
+    ACoord { x: 0, y: -1 },
+    ACoord { x: -1, y: 0 },
+    ACoord { x: -1, y: 1 },
+    ACoord { x: 0, y: 1 },
+];
+
 impl fmt::Display for ACoord {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let _ = writeln!(f, "(x: {:?}, y: {:?})", self.x, self.y);
         Ok(())
     }
 }
+
+/// Voxel coordinate, z points upward through the layers
+#[derive(Eq, PartialEq, Copy, Clone, Hash, Debug)]
+pub struct VCoord {
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
+
+/// Face adjacent voxels
+pub const FACES: [VCoord; 6] = [
+    VCoord { x: 1, y: 0, z: 0 },
+    VCoord { x: -1, y: 0, z: 0 },
+    VCoord { x: 0, y: 1, z: 0 },
+    VCoord { x: 0, y: -1, z: 0 },
+    VCoord { x: 0, y: 0, z: 1 },
+    VCoord { x: 0, y: 0, z: -1 },
+];
+
+/// Every voxel sharing a face, edge or corner, the 3D compass rose
+pub const VOXEL_ROSE: [VCoord; 26] = [
+    VCoord { x: 1, y: 0, z: 0 },
+    VCoord { x: -1, y: 0, z: 0 },
+    VCoord { x: 0, y: 1, z: 0 },
+    VCoord { x: 1, y: 1, z: 0 },
+    VCoord { x: -1, y: 1, z: 0 },
+    VCoord { x: 0, y: -1, z: 0 },
+    VCoord { x: 1, y: -1, z: 0 },
+    VCoord { x: -1, y: -1, z: 0 },
+    VCoord { x: 0, y: 0, z: 1 },
+    VCoord { x: 1, y: 0, z: 1 },
+    VCoord { x: -1, y: 0, z: 1 },
+    VCoord { x: 0, y: 1, z: 1 },
+    VCoord { x: 1, y: 1, z: 1 },
+    VCoord { x: -1, y: 1, z: 1 },
+    VCoord { x: 0, y: -1, z: 1 },
+    VCoord { x: 1, y: -1, z: 1 },
+    VCoord { x: -1, y: -1, z: 1 },
+    VCoord { x: 0, y: 0, z: -1 },
+    VCoord { x: 1, y: 0, z: -1 },
+    VCoord { x: -1, y: 0, z: -1 },
+    VCoord { x: 0, y: 1, z: -1 },
+    VCoord { x: 1, y: 1, z: -1 },
+    VCoord { x: -1, y: 1, z: -1 },
+    VCoord { x: 0, y: -1, z: -1 },
+    VCoord { x: 1, y: -1, z: -1 },
+    VCoord { x: -1, y: -1, z: -1 },
+];
@@ -76,7 +76,8 @@ where
     pub fn peek(&mut self) -> Option<(S, M)> {
         loop {
             let k = self.index.peek()?;
-            if self.map.contains_key(&k.identity) {
+            // entries pushed again carry newer measures, older copies are skipped
+            if self.map.get(&k.identity) == Some(&k.measure) {
                 return Some((k.identity, k.measure));
             }
             // heap has removed the element alligning index with map
@@ -85,38 +86,13 @@ where
     }
     pub fn pop(&mut self) -> Option<(S, M)> {
         while let Some(k) = self.index.pop() {
-            if !self.map.contains_key(&k.identity) {
-                continue;
+            if self.map.get(&k.identity) == Some(&k.measure) {
+                self.map.remove(&k.identity);
+                return Some((k.identity, k.measure));
             }
-            let _v = self.map.remove(&k.identity)?;
-            return Some((k.identity, k.measure));
         }
         None
     }
-    // pub fn peek(&mut self) -> Option<(S, M)> {
-    //     loop {
-    //         let k = self.index.peek()?;
-    //         if let Some(v) = self.map.get(&k.identity) {
-    //             if k.measure == *v {
-    //                 return Some((k.identity, *v));
-    //             }
-    //         }
-    //         // heap has removed the element alligning index with map
-    //         self.index.pop();
-    //     }
-    // }
-    // pub fn pop(&mut self) -> Option<(S, M)> {
-    //     while let Some(k) = self.index.pop() {
-    //         if !self.map.contains_key(&k.identity) {
-    //             continue;
-    //         }
-    //         if k.measure == self.map[&k.identity] {
-    //             let v = self.map.remove(&k.identity)?;
-    //             return Some((k.identity, v));
-    //         }
-    //     }
-    //     None
-    // }
     pub fn push(&mut self, identity: S, measure: M) {
         self.index.push(IndexNode { identity, measure });
         self.map.insert(identity, measure);
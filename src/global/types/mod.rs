@@ -6,7 +6,10 @@ pub mod lazy_pqueue;
 pub mod min_heap;
 pub mod monolithic;
 pub mod plan;
+pub mod pose;
+pub mod search_graph;
 pub mod spatial_map;
+pub mod volumetric_map;
 
 pub use coordinate::*;
 pub use heur_heap::*;
@@ -16,4 +19,7 @@ pub use lazy_pqueue::*;
 pub use min_heap::*;
 pub use monolithic::*;
 pub use plan::*;
+pub use pose::*;
+pub use search_graph::*;
 pub use spatial_map::*;
+pub use volumetric_map::*;
@@ -1,7 +1,8 @@
+use crate::global::types::{ACoord, CARDINALS, COMPASS_ROSE};
 use std::hash::Hash;
 
 pub type Coord = (usize, usize);
-#[derive(Clone, Debug)]
+#[derive(Eq, PartialEq, Clone, Debug)]
 pub struct Bounds {
     pub min_x: isize,
     pub min_y: isize,
@@ -9,6 +10,28 @@ pub struct Bounds {
     pub max_y: isize,
 }
 
+/// Rectangle queries treat both min and max as inclusive
+impl Bounds {
+    pub fn contains(&self, coord: ACoord) -> bool {
+        self.min_x <= coord.x
+            && coord.x <= self.max_x
+            && self.min_y <= coord.y
+            && coord.y <= self.max_y
+    }
+    pub fn intersects(&self, other: &Bounds) -> bool {
+        self.min_x <= other.max_x
+            && other.min_x <= self.max_x
+            && self.min_y <= other.max_y
+            && other.min_y <= self.max_y
+    }
+    pub fn encloses(&self, other: &Bounds) -> bool {
+        self.min_x <= other.min_x
+            && other.max_x <= self.max_x
+            && self.min_y <= other.min_y
+            && other.max_y <= self.max_y
+    }
+}
+
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum Belief {
     Free,
@@ -16,6 +39,82 @@ pub enum Belief {
     Unknown,
 }
 
+/// Visibility of cells whose belief is unknown
+///
+/// * Transparent := optimistic, unknown space does not block sight
+/// * Opaque := conservative, unknown space blocks sight
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Opacity {
+    Transparent,
+    Opaque,
+}
+
+impl Belief {
+    /// Whether the belief blocks a line of sight
+    pub fn blocks(&self, unknown: Opacity) -> bool {
+        match self {
+            Belief::Free => false,
+            Belief::Occupied => true,
+            Belief::Unknown => unknown == Opacity::Opaque,
+        }
+    }
+}
+
+/// Cost of a straight step under octile and euclidean connectivity
+pub const STRAIGHT_COST: usize = 10;
+/// Cost of a diagonal step under octile connectivity, sqrt(2) scaled
+pub const DIAGONAL_COST: usize = 14;
+
+/// Moves permitted between cells and the cost of taking them
+///
+/// * Four := cardinal moves, each step costs one
+/// * Octile := compass rose moves, straight and diagonal steps cost 10 and 14
+/// * Euclidean := compass rose moves, euclidean length scaled by 10 rounded up
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
+pub enum Connectivity {
+    #[default]
+    Four,
+    Octile,
+    Euclidean,
+}
+
+impl Connectivity {
+    pub fn directions(&self) -> &'static [ACoord] {
+        match self {
+            Connectivity::Four => &CARDINALS,
+            Connectivity::Octile | Connectivity::Euclidean => &COMPASS_ROSE,
+        }
+    }
+    /// Cost of travelling between two cells, consistent with the step costs
+    pub fn cost(&self, a: ACoord, b: ACoord) -> usize {
+        let (dx, dy) = (a.x.abs_diff(b.x), a.y.abs_diff(b.y));
+        match self {
+            Connectivity::Four => dx + dy,
+            Connectivity::Octile => {
+                let (lo, hi) = (dx.min(dy), dx.max(dy));
+                DIAGONAL_COST * lo + STRAIGHT_COST * (hi - lo)
+            }
+            Connectivity::Euclidean => {
+                // rounding up keeps the estimate a lower bound of summed steps
+                let length = ((dx * dx + dy * dy) as f64).sqrt();
+                (length * STRAIGHT_COST as f64).ceil() as usize
+            }
+        }
+    }
+    /// Whether a diagonal step squeezes past an occupied cell
+    pub fn cuts_corner(coord: ACoord, step: ACoord, occupied: impl Fn(ACoord) -> bool) -> bool {
+        step.x != 0
+            && step.y != 0
+            && (occupied(ACoord {
+                x: coord.x + step.x,
+                y: coord.y,
+            }) || occupied(ACoord {
+                x: coord.x,
+                y: coord.y + step.y,
+            }))
+    }
+}
+
 #[derive(Eq, PartialEq, Debug)]
 pub enum Status {
     Enroute,
@@ -30,3 +129,12 @@ pub struct HCoord {
     pub x: isize,
     pub y: isize,
 }
+
+/// Hierarchical voxel coordinate of the octree, the 3D HCoord
+#[derive(Clone, Copy, Debug, Hash, Eq, PartialEq)]
+pub struct OCoord {
+    pub l: usize,
+    pub x: isize,
+    pub y: isize,
+    pub z: isize,
+}
@@ -1,5 +1,6 @@
-use crate::global::types::ACoord;
-use crate::global::types::SpatialMap;
+use crate::global::types::{ACoord, Belief};
+use crate::global::types::{Frame, Pose, SpatialMap, VCoord, VolumetricMap};
+use std::time::Duration;
 
 pub struct BackwardIter<'a> {
     nodes: &'a [ACoord],
@@ -71,31 +72,107 @@ pub trait PlanIter {
     fn nodes(&self) -> &[ACoord];
 }
 
-#[derive(Debug)]
-pub struct AStarPlan {
-    pub plan: Vec<ACoord>,
+/// How a planner arrived at a plan
+///
+/// * Computed := searched from scratch
+/// * Repaired := earlier search effort was reused
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
+pub enum Provenance {
+    #[default]
+    Computed,
+    Repaired,
 }
 
-#[derive(Debug)]
-pub struct DStarPlan {
-    pub plan: Vec<ACoord>,
+/// Work done by the search behind a plan
+///
+/// # Attributes #
+/// * expanded := nodes taken off the open list and expanded
+/// * pushes := entries pushed onto the open list
+/// * elapsed := wall clock spent planning
+/// * provenance := whether the plan was computed or repaired
+#[derive(Eq, PartialEq, Copy, Clone, Debug, Default)]
+pub struct SearchStats {
+    pub expanded: usize,
+    pub pushes: usize,
+    pub elapsed: Duration,
+    pub provenance: Provenance,
 }
 
+/// Cells to visit from the source, the source excluded and the target last
+///
+/// # Attributes #
+/// * plan := cells in walking order
+/// * steps := cost of the step onto each cell
+/// * cost := cost of the whole plan
+/// * bound := suboptimality bound, for planners which guarantee one
+/// * stats := how the plan was found
 #[derive(Debug)]
-pub struct BestFirstPlan {
+pub struct Plan {
     pub plan: Vec<ACoord>,
+    pub steps: Vec<usize>,
+    pub cost: usize,
+    pub bound: Option<f64>,
+    pub stats: SearchStats,
 }
 
-impl PlanIter for AStarPlan {
-    fn nodes(&self) -> &[ACoord] {
-        &self.plan
+impl Plan {
+    /// Plan through the cells in walking order, costed on the map
+    pub fn new<S: SpatialMap>(
+        env: &S,
+        source: ACoord,
+        cells: Vec<ACoord>,
+        stats: SearchStats,
+    ) -> Self {
+        let mut plan = Vec::with_capacity(cells.len());
+        let mut steps = Vec::with_capacity(cells.len());
+        let mut prev = source;
+        for cell in cells {
+            // repeated cells add nothing to walk
+            if cell == prev {
+                continue;
+            }
+            steps.push(env.distance(env.encode(prev), env.encode(cell)));
+            plan.push(cell);
+            prev = cell;
+        }
+        Self::from_steps(plan, steps, stats)
     }
-    fn iter(&self) -> impl Iterator<Item = &ACoord> {
-        BackwardIter::new(self.nodes())
+    /// Plan through waypoints walked in straight lines, costed on the map by their length
+    pub fn segments<S: SpatialMap>(
+        env: &S,
+        source: ACoord,
+        waypoints: Vec<ACoord>,
+        stats: SearchStats,
+    ) -> Self {
+        let mut plan = Vec::with_capacity(waypoints.len());
+        let mut steps = Vec::with_capacity(waypoints.len());
+        let mut prev = source;
+        for waypoint in waypoints {
+            if waypoint == prev {
+                continue;
+            }
+            steps.push(env.segment(prev, waypoint));
+            plan.push(waypoint);
+            prev = waypoint;
+        }
+        Self::from_steps(plan, steps, stats)
+    }
+    fn from_steps(plan: Vec<ACoord>, steps: Vec<usize>, stats: SearchStats) -> Self {
+        Self {
+            plan,
+            cost: steps.iter().sum(),
+            steps,
+            bound: None,
+            stats,
+        }
+    }
+    pub fn with_bound(mut self, bound: f64) -> Self {
+        self.bound = Some(bound);
+        self
     }
 }
 
-impl PlanIter for DStarPlan {
+impl PlanIter for Plan {
     fn nodes(&self) -> &[ACoord] {
         &self.plan
     }
@@ -104,17 +181,82 @@ impl PlanIter for DStarPlan {
     }
 }
 
-impl PlanIter for BestFirstPlan {
-    fn nodes(&self) -> &[ACoord] {
+/// Belief of a cell before and after the map recorded new information
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub struct CellChange {
+    pub coord: ACoord,
+    pub old: Belief,
+    pub new: Belief,
+}
+
+impl CellChange {
+    /// Change of the cell, none when the belief stayed the same
+    pub fn between(coord: ACoord, old: Belief, new: Belief) -> Option<Self> {
+        (old != new).then_some(Self { coord, old, new })
+    }
+}
+
+pub trait Planner<S: SpatialMap> {
+    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Plan>;
+    /// Informs the planner of cells whose beliefs changed, the map already holds the new beliefs
+    fn update(&mut self, env: &S, changes: &[CellChange]);
+    /// Plans between the cells containing the continuous poses
+    fn plan_pose(&mut self, env: &S, frame: &Frame, source: &Pose, target: &Pose) -> Option<Plan> {
+        self.plan(env, frame.cell(source), frame.cell(target))
+    }
+}
+
+/// Voxels to visit from the source, the source excluded and the target last
+///
+/// # Attributes #
+/// * plan := voxels in walking order
+/// * steps := cost of the step onto each voxel
+/// * cost := cost of the whole plan
+/// * stats := how the plan was found
+#[derive(Debug)]
+pub struct VoxelPlan {
+    pub plan: Vec<VCoord>,
+    pub steps: Vec<usize>,
+    pub cost: usize,
+    pub stats: SearchStats,
+}
+
+impl VoxelPlan {
+    /// Plan through the voxels in walking order, costed on the map
+    pub fn new<V: VolumetricMap>(
+        env: &V,
+        source: VCoord,
+        voxels: Vec<VCoord>,
+        stats: SearchStats,
+    ) -> Self {
+        let mut plan = Vec::with_capacity(voxels.len());
+        let mut steps = Vec::with_capacity(voxels.len());
+        let mut prev = source;
+        for voxel in voxels {
+            if voxel == prev {
+                continue;
+            }
+            steps.push(env.distance(env.encode(prev), env.encode(voxel)));
+            plan.push(voxel);
+            prev = voxel;
+        }
+        Self {
+            plan,
+            cost: steps.iter().sum(),
+            steps,
+            stats,
+        }
+    }
+    pub fn nodes(&self) -> &[VCoord] {
         &self.plan
     }
-    fn iter(&self) -> impl Iterator<Item = &ACoord> {
-        BackwardIter::new(self.nodes())
+    /// Voxels from the first step to the target
+    pub fn iter(&self) -> impl Iterator<Item = &VCoord> {
+        self.plan.iter()
     }
 }
 
-pub trait Planner<S: SpatialMap> {
-    type Plan: PlanIter;
-    fn plan(&mut self, env: &S, source: ACoord, target: ACoord) -> Option<Self::Plan>;
-    fn update(&mut self, env: &S, position: ACoord, obstacle: ACoord);
+/// Planner counterpart for volumetric maps
+pub trait VolumePlanner<V: VolumetricMap> {
+    fn plan_volume(&mut self, env: &V, source: VCoord, target: VCoord) -> Option<VoxelPlan>;
 }
@@ -0,0 +1,86 @@
+use crate::global::types::ACoord;
+
+/// Continuous pose, heading in radians counter-clockwise from the x axis
+#[derive(PartialEq, Copy, Clone, Debug, Default)]
+pub struct Pose {
+    pub x: f64,
+    pub y: f64,
+    pub heading: f64,
+}
+
+impl Pose {
+    pub fn new(x: f64, y: f64, heading: f64) -> Self {
+        Self { x, y, heading }
+    }
+    /// Pose after travelling straight to (x, y), facing the direction of travel
+    pub fn towards(&self, x: f64, y: f64) -> Self {
+        let (dx, dy) = (x - self.x, y - self.y);
+        let heading = if dx == 0.0 && dy == 0.0 {
+            self.heading
+        } else {
+            dy.atan2(dx)
+        };
+        Self { x, y, heading }
+    }
+}
+
+/// Placement of the map within the world
+///
+/// Map space is continuous in cell units, cell (x, y) spans [x, x + 1) x [y, y + 1).
+///
+/// # Attributes #
+/// * resolution := world units along the side of a cell
+/// * origin := world pose of the map corner, its heading rotates the map axes
+#[derive(PartialEq, Copy, Clone, Debug)]
+pub struct Frame {
+    pub resolution: f64,
+    pub origin: Pose,
+}
+
+impl Default for Frame {
+    fn default() -> Self {
+        Self {
+            resolution: 1.0,
+            origin: Pose::default(),
+        }
+    }
+}
+
+impl Frame {
+    pub fn new(resolution: f64, origin: Pose) -> Self {
+        assert!(resolution > 0.0, "resolution must be positive");
+        Self { resolution, origin }
+    }
+    /// World pose expressed in map space
+    pub fn to_map(&self, pose: &Pose) -> Pose {
+        let (sin, cos) = self.origin.heading.sin_cos();
+        let (dx, dy) = (pose.x - self.origin.x, pose.y - self.origin.y);
+        Pose {
+            x: (cos * dx + sin * dy) / self.resolution,
+            y: (cos * dy - sin * dx) / self.resolution,
+            heading: pose.heading - self.origin.heading,
+        }
+    }
+    /// Map space pose expressed in the world
+    pub fn to_world(&self, pose: &Pose) -> Pose {
+        let (sin, cos) = self.origin.heading.sin_cos();
+        let (mx, my) = (pose.x * self.resolution, pose.y * self.resolution);
+        Pose {
+            x: self.origin.x + cos * mx - sin * my,
+            y: self.origin.y + sin * mx + cos * my,
+            heading: pose.heading + self.origin.heading,
+        }
+    }
+    /// Cell containing the world pose
+    pub fn cell(&self, pose: &Pose) -> ACoord {
+        let local = self.to_map(pose);
+        ACoord {
+            x: local.x.floor() as isize,
+            y: local.y.floor() as isize,
+        }
+    }
+    /// World pose at the centre of the cell, aligned with the map axes
+    pub fn centre(&self, cell: ACoord) -> Pose {
+        self.to_world(&Pose::new(cell.x as f64 + 0.5, cell.y as f64 + 0.5, 0.0))
+    }
+}
@@ -0,0 +1,45 @@
+use crate::global::types::{Belief, SpatialMap, VolumetricMap};
+use std::hash::Hash;
+
+/// Nodes, adjacency and step costs a graph search walks
+///
+/// Flat and layered maps are searched through the Surface and Volume views,
+/// so a search loop is written once for both.
+pub trait SearchGraph {
+    type Node: Copy + Eq + PartialEq + Hash;
+    fn belief(&self, node: Self::Node) -> Belief;
+    fn neighbors(&self, node: Self::Node) -> Vec<Self::Node>;
+    fn distance(&self, a: Self::Node, b: Self::Node) -> usize;
+}
+
+/// Search view of a SpatialMap over its encoded nodes
+pub struct Surface<'a, S>(pub &'a S);
+
+impl<S: SpatialMap> SearchGraph for Surface<'_, S> {
+    type Node = S::Encoded;
+    fn belief(&self, node: Self::Node) -> Belief {
+        self.0.belief(node)
+    }
+    fn neighbors(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.0.neighbors(node)
+    }
+    fn distance(&self, a: Self::Node, b: Self::Node) -> usize {
+        self.0.distance(a, b)
+    }
+}
+
+/// Search view of a VolumetricMap over its encoded nodes
+pub struct Volume<'a, V>(pub &'a V);
+
+impl<V: VolumetricMap> SearchGraph for Volume<'_, V> {
+    type Node = V::Encoded;
+    fn belief(&self, node: Self::Node) -> Belief {
+        self.0.belief(node)
+    }
+    fn neighbors(&self, node: Self::Node) -> Vec<Self::Node> {
+        self.0.neighbors(node)
+    }
+    fn distance(&self, a: Self::Node, b: Self::Node) -> usize {
+        self.0.distance(a, b)
+    }
+}
@@ -1,19 +1,97 @@
-use crate::global::types::{ACoord, Belief};
+use crate::global::line::supercover;
+use crate::global::types::plan::CellChange;
+use crate::global::types::{ACoord, Belief, Bounds, Opacity};
 use std::hash::Hash;
 
+/// Rectangle of cells sharing a belief
+///
+/// # Attributes #
+/// * bounds := inclusive rectangle of cells
+/// * belief := belief of every covered cell
+#[derive(Eq, PartialEq, Clone, Debug)]
+pub struct Region {
+    pub bounds: Bounds,
+    pub belief: Belief,
+}
+
 pub trait SpatialMap {
     type Encoded: Copy + Eq + PartialEq + Hash;
     // // sensor-facing (world space)
-    fn insert_ray(&mut self, pos: ACoord, hit: ACoord);
+    /// Records the cells up to the hit as free and the hit as occupied
+    ///
+    /// Returns the cells whose belief changed.
+    fn insert_ray(&mut self, pos: ACoord, hit: ACoord) -> Vec<CellChange>;
+    /// Records the cells a beam crossed as free and its hit as occupied
+    ///
+    /// Returns the cells whose belief changed.
+    fn insert_beam(&mut self, crossed: &[ACoord], hit: ACoord) -> Vec<CellChange> {
+        let mut changes = Vec::new();
+        let cells = crossed.iter().map(|&c| (c, Belief::Free));
+        for (cell, belief) in cells.chain([(hit, Belief::Occupied)]) {
+            let old = self.belief(self.encode(cell));
+            let rect = Bounds {
+                min_x: cell.x,
+                min_y: cell.y,
+                max_x: cell.x,
+                max_y: cell.y,
+            };
+            self.fill_region(&rect, belief);
+            changes.extend(CellChange::between(cell, old, belief));
+        }
+        changes
+    }
     fn obstructed(&self, coord: ACoord) -> bool;
     // // planner-facing (encoded space)
     fn belief(&self, node: Self::Encoded) -> Belief;
     fn neighbors(&self, node: Self::Encoded) -> Vec<Self::Encoded>;
     fn distance(&self, a: Self::Encoded, b: Self::Encoded) -> usize;
+    /// Cost of walking straight between two cells, in the units of distance
+    ///
+    /// The length of the segment scaled by the cost of one straight step.
+    fn segment(&self, a: ACoord, b: ACoord) -> usize {
+        let beside = ACoord { x: a.x + 1, y: a.y };
+        let unit = self.distance(self.leaf(a), self.leaf(beside));
+        let (dx, dy) = (a.x.abs_diff(b.x), a.y.abs_diff(b.y));
+        (((dx * dx + dy * dy) as f64).sqrt() * unit as f64).ceil() as usize
+    }
     // bridge
     fn encode(&self, coord: ACoord) -> Self::Encoded;
     fn leaf(&self, coord: ACoord) -> Self::Encoded;
     fn decode(&self, node: Self::Encoded) -> ACoord;
+    /// Nodes now covering the cells of a node, which may since have been merged or split
+    fn covering(&self, node: Self::Encoded) -> Vec<Self::Encoded> {
+        vec![self.encode(self.decode(node))]
+    }
     // plan-initialization
     fn initialize(&mut self, source: ACoord, target: ACoord);
+    // interchange (world space)
+    /// Regions covering every cell the map holds a belief for
+    fn regions(&self) -> Vec<Region>;
+    /// Sets the belief of every cell in the inclusive rectangle
+    fn fill_region(&mut self, rect: &Bounds, belief: Belief);
+    // visibility (world space, belief)
+    /// Whether every cell touched by the segment a -> b is see-through
+    fn line_of_sight(&self, a: ACoord, b: ACoord, unknown: Opacity) -> bool {
+        supercover(a, b)
+            .into_iter()
+            .all(|c| !self.belief(self.encode(c)).blocks(unknown))
+    }
+    /// First blocking cell marching from origin in steps of direction
+    fn raycast(
+        &self,
+        origin: ACoord,
+        direction: ACoord,
+        max_range: usize,
+        unknown: Opacity,
+    ) -> Option<ACoord> {
+        let mut n_xy = origin;
+        for _ in 0..max_range {
+            n_xy.x += direction.x;
+            n_xy.y += direction.y;
+            if self.belief(self.encode(n_xy)).blocks(unknown) {
+                return Some(n_xy);
+            }
+        }
+        None
+    }
 }
@@ -0,0 +1,39 @@
+use crate::global::types::{Belief, Opacity, VCoord};
+use std::hash::Hash;
+
+/// Voxel counterpart of SpatialMap for maps spanning several layers
+pub trait VolumetricMap {
+    type Encoded: Copy + Eq + PartialEq + Hash;
+    // // sensor-facing (world space)
+    fn insert_ray(&mut self, pos: VCoord, hit: VCoord);
+    fn obstructed(&self, coord: VCoord) -> bool;
+    // // planner-facing (encoded space)
+    fn belief(&self, node: Self::Encoded) -> Belief;
+    fn neighbors(&self, node: Self::Encoded) -> Vec<Self::Encoded>;
+    fn distance(&self, a: Self::Encoded, b: Self::Encoded) -> usize;
+    // bridge
+    fn encode(&self, coord: VCoord) -> Self::Encoded;
+    fn leaf(&self, coord: VCoord) -> Self::Encoded;
+    fn decode(&self, node: Self::Encoded) -> VCoord;
+    // plan-initialization
+    fn initialize(&mut self, source: VCoord, target: VCoord);
+    /// First blocking voxel marching from origin in steps of direction
+    fn raycast(
+        &self,
+        origin: VCoord,
+        direction: VCoord,
+        max_range: usize,
+        unknown: Opacity,
+    ) -> Option<VCoord> {
+        let mut n_xyz = origin;
+        for _ in 0..max_range {
+            n_xyz.x += direction.x;
+            n_xyz.y += direction.y;
+            n_xyz.z += direction.z;
+            if self.belief(self.encode(n_xyz)).blocks(unknown) {
+                return Some(n_xyz);
+            }
+        }
+        None
+    }
+}
@@ -1,4 +1,4 @@
-use crate::global::types::{ACoord, HCoord};
+use crate::global::types::{ACoord, Bounds, HCoord};
 
 // // Hestereses or defered clean up
 // // Consdier implementing a jump iter
@@ -19,10 +19,10 @@ pub fn point(h_coord: HCoord) -> ACoord {
     }
 }
 
-/// floating point representation of centroid of hierarchy
+/// floating point centroid of hierarchy in map space, cell (x, y) spans [x, x + 1)
 pub fn decode_hier(coord: HCoord) -> (f32, f32) {
-    let dp = point(coord);
-    ((dp.x - 1) as f32 / 2.0, (dp.x - 1) as f32 / 2.0)
+    let half = (1 << coord.l) as f32 / 2.0;
+    (coord.x as f32 + half, coord.y as f32 + half)
 }
 
 pub fn encode(coord: ACoord, level: usize) -> HCoord {
@@ -34,6 +34,22 @@ pub fn encode(coord: ACoord, level: usize) -> HCoord {
     }
 }
 
+/// Inclusive rectangle of cells covered by the hierarchical node
+pub fn extent(node: &HCoord) -> Bounds {
+    let span = 1 << node.l;
+    Bounds {
+        min_x: node.x,
+        min_y: node.y,
+        max_x: node.x + span - 1,
+        max_y: node.y + span - 1,
+    }
+}
+
+/// Whether the hierarchical node covers the coordinate
+pub fn covers(node: &HCoord, coord: ACoord) -> bool {
+    encode(coord, node.l) == *node
+}
+
 pub fn transform(coord: &HCoord, level: usize) -> HCoord {
     if level < coord.l {
         return *coord;
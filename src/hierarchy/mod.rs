@@ -1,2 +1,4 @@
 pub mod encoding;
+pub mod morton;
+pub mod octal;
 pub mod proximity;
@@ -0,0 +1,89 @@
+use crate::global::types::{ACoord, HCoord};
+
+// Locational codes interleave the bits of x (even) and y (odd) so a node at
+// level l owns the contiguous range [code, code + 4^l) in Z-order.
+
+/// Shift making every coordinate within +-2^31 non negative before interleaving
+const OFFSET: isize = 1 << 31;
+const X_MASK: u64 = 0x5555_5555_5555_5555;
+const Y_MASK: u64 = 0xAAAA_AAAA_AAAA_AAAA;
+
+/// Spaces the bits of v apart, ie 0b111 -> 0b10101
+fn spread(v: u32) -> u64 {
+    let mut x = v as u64;
+    x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+    (x | (x << 1)) & X_MASK
+}
+
+/// Inverse of spread, gathers the even bits
+fn compact(v: u64) -> u32 {
+    let mut x = v & X_MASK;
+    x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+    ((x | (x >> 16)) & 0xFFFF_FFFF) as u32
+}
+
+pub fn morton(coord: ACoord) -> u64 {
+    spread((coord.x + OFFSET) as u32) | (spread((coord.y + OFFSET) as u32) << 1)
+}
+
+pub fn demorton(code: u64) -> ACoord {
+    ACoord {
+        x: compact(code) as isize - OFFSET,
+        y: compact(code >> 1) as isize - OFFSET,
+    }
+}
+
+/// Locational code of a hierarchical node, the code of its lower left cell
+pub fn locate(node: HCoord) -> u64 {
+    morton(ACoord {
+        x: node.x,
+        y: node.y,
+    })
+}
+
+/// Hierarchical node owning the code at the level
+pub fn node(code: u64, level: usize) -> HCoord {
+    let coord = demorton(parent(code, level));
+    HCoord {
+        l: level,
+        x: coord.x,
+        y: coord.y,
+    }
+}
+
+/// Number of cells, and so of codes, owned by a node at the level
+pub fn span(level: usize) -> u64 {
+    1 << (2 * level)
+}
+
+/// Code of the node at the level containing the code
+pub fn parent(code: u64, level: usize) -> u64 {
+    code & !(span(level) - 1)
+}
+
+/// Z-ordered children of the node at the level, matches child_hier
+pub fn children(code: u64, level: usize) -> [u64; 4] {
+    let quarter = span(level - 1);
+    [code, code + quarter, code + 2 * quarter, code + 3 * quarter]
+}
+
+/// Code displaced by (dx, dy) cells through dilated integer arithmetic
+pub fn step(code: u64, dx: isize, dy: isize) -> u64 {
+    let x = dilated_add(code, X_MASK, spread(dx.unsigned_abs() as u32), dx < 0);
+    let y = dilated_add(code, Y_MASK, spread(dy.unsigned_abs() as u32) << 1, dy < 0);
+    x | y
+}
+
+fn dilated_add(code: u64, mask: u64, delta: u64, negative: bool) -> u64 {
+    if negative {
+        ((code & mask).wrapping_sub(delta)) & mask
+    } else {
+        ((code | !mask).wrapping_add(delta)) & mask
+    }
+}
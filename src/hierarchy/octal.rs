@@ -0,0 +1,57 @@
+use crate::global::types::{OCoord, VCoord};
+
+/// Centroid of the octree node scaled by two in order to prevent half-integers
+pub fn point(node: OCoord) -> VCoord {
+    let span = 1 << node.l;
+    VCoord {
+        x: 2 * node.x + span,
+        y: 2 * node.y + span,
+        z: 2 * node.z + span,
+    }
+}
+
+pub fn encode(coord: VCoord, level: usize) -> OCoord {
+    let mask = !((1 << level) - 1);
+    OCoord {
+        l: level,
+        x: coord.x & mask,
+        y: coord.y & mask,
+        z: coord.z & mask,
+    }
+}
+
+/// Whether the octree node covers the voxel
+pub fn covers(node: &OCoord, coord: VCoord) -> bool {
+    encode(coord, node.l) == *node
+}
+
+pub fn transform(coord: &OCoord, level: usize) -> OCoord {
+    if level < coord.l {
+        return *coord;
+    }
+    let mask = !((1 << level) - 1);
+    OCoord {
+        l: level,
+        x: coord.x & mask,
+        y: coord.y & mask,
+        z: coord.z & mask,
+    }
+}
+
+/// Octants of the node, x varies fastest then y then z
+pub fn child_hier(hier: &OCoord) -> [OCoord; 8] {
+    // level of the child
+    let l = hier.l - 1;
+    // separation of the grid
+    let dh = 1 << l;
+    let mut children = [*hier; 8];
+    for (i, child) in children.iter_mut().enumerate() {
+        *child = OCoord {
+            l,
+            x: hier.x + dh * (i & 1) as isize,
+            y: hier.y + dh * ((i >> 1) & 1) as isize,
+            z: hier.z + dh * ((i >> 2) & 1) as isize,
+        };
+    }
+    children
+}
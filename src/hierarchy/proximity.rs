@@ -1,6 +1,6 @@
 use crate::environment::quad::QuadTree;
-use crate::global::types::{Belief, HCoord};
-use crate::hierarchy::encoding::transform;
+use crate::global::types::{ACoord, Belief, Connectivity, HCoord, SpatialMap};
+use crate::hierarchy::encoding::{encode, extent, transform};
 
 // // Observation Logic (Unknown \(\rightarrow \) Free/Occupied).LU Pivoting (Numerical insurance)
 // // Multi-ray LiDAR & Planner Implementation.
@@ -108,6 +108,14 @@ pub fn south_hier(node: HCoord) -> [HCoord; 2] {
     ]
 }
 pub fn edge_neighbors(quad: &QuadTree, node: HCoord) -> Vec<HCoord> {
+    edge_leaves(quad, node)
+        .into_iter()
+        .filter(|n| quad.information[n].belief != Belief::Occupied)
+        .collect()
+}
+
+/// Leaves sharing an edge with the node whatever their belief
+pub fn edge_leaves(quad: &QuadTree, node: HCoord) -> Vec<HCoord> {
     // neighbor and filter need to be opposites ie (neigh east -> filter west);
     let cardinals = find_cardinals(node);
     // opposite of clockwise iteration
@@ -128,10 +136,8 @@ pub fn edge_neighbors(quad: &QuadTree, node: HCoord) -> Vec<HCoord> {
             if e_node == h_node {
                 // information is more granular
                 break;
-            } else if let Some(n) = quad.information.get(&e_node) {
-                if n.belief != Belief::Occupied {
-                    neighbors.push(e_node);
-                }
+            } else if quad.information.contains_key(&e_node) {
+                neighbors.push(e_node);
                 found = true;
                 break;
             }
@@ -141,10 +147,7 @@ pub fn edge_neighbors(quad: &QuadTree, node: HCoord) -> Vec<HCoord> {
         }
         stack.push(cardinal);
         while let Some(p_coord) = stack.pop() {
-            if let Some(n) = quad.information.get(&p_coord) {
-                if n.belief == Belief::Occupied {
-                    continue;
-                }
+            if quad.information.contains_key(&p_coord) {
                 neighbors.push(p_coord);
             } else if p_coord.l > 0 {
                 stack.extend(filter(p_coord));
@@ -153,3 +156,31 @@ pub fn edge_neighbors(quad: &QuadTree, node: HCoord) -> Vec<HCoord> {
     }
     neighbors
 }
+
+/// Leaves diagonally across the corners of a node which are reachable without cutting a corner
+pub fn corner_neighbors(quad: &QuadTree, node: HCoord) -> Vec<HCoord> {
+    let rect = extent(&node);
+    let corners = [
+        (rect.max_x, rect.max_y, ACoord { x: 1, y: 1 }),
+        (rect.min_x, rect.max_y, ACoord { x: -1, y: 1 }),
+        (rect.min_x, rect.min_y, ACoord { x: -1, y: -1 }),
+        (rect.max_x, rect.min_y, ACoord { x: 1, y: -1 }),
+    ];
+    let mut neighbors = Vec::new();
+    for (x, y, d) in corners {
+        let corner = ACoord { x, y };
+        if Connectivity::cuts_corner(corner, d, |c| quad.obstructed(c)) {
+            continue;
+        }
+        let diagonal = ACoord {
+            x: x + d.x,
+            y: y + d.y,
+        };
+        if let Some((lvl, belief)) = quad.get_coord(diagonal)
+            && belief != Belief::Occupied
+        {
+            neighbors.push(encode(diagonal, lvl));
+        }
+    }
+    neighbors
+}
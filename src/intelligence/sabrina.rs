@@ -1,49 +1,71 @@
+use crate::environment::grid::Grid;
+use crate::global::line::Traversal;
 use crate::global::types::plan::PlanIter;
-use crate::global::types::{ACoord, Status};
+use crate::global::types::{ACoord, Frame, Pose, Status};
 use crate::global::types::{Planner, SpatialMap};
 use crate::sensor::lidar::Lidar;
 use std::fmt::{Debug, Display};
 
-pub struct Sabrina<S, P>
+pub struct Sabrina<S, P, O = Grid>
 where
     S: SpatialMap,
     P: Planner<S>,
 {
     pub environment: S,
-    pub lidar: Lidar,
+    pub lidar: Lidar<O>,
     pub planner: P,
     pub position: ACoord,
+    // continuous pose within the world, position is the cell containing it
+    pub pose: Pose,
+    pub frame: Frame,
 }
 
-impl<S, P> Sabrina<S, P>
+impl<S, P, O> Sabrina<S, P, O>
 where
     S: SpatialMap + Display,
     P: Planner<S>,
-    P::Plan: Debug,
+    O: SpatialMap,
 {
-    pub fn new(position: ACoord, environment: S, lidar: Lidar, planner: P) -> Self {
+    pub fn new(position: ACoord, environment: S, lidar: Lidar<O>, planner: P) -> Self {
+        let frame = Frame::default();
+        Self::with_frame(frame.centre(position), frame, environment, lidar, planner)
+    }
+    /// Sabrina placed at a continuous pose, cells are laid out by the frame
+    pub fn with_frame(
+        pose: Pose,
+        frame: Frame,
+        environment: S,
+        lidar: Lidar<O>,
+        planner: P,
+    ) -> Self {
         Self {
             environment,
             lidar,
             planner,
-            position,
+            position: frame.cell(&pose),
+            pose,
+            frame,
         }
     }
     fn scan(&mut self) {
-        // These will need to be rotated in order to account for orrientation
-        let measure = self.lidar.measure(self.position);
-        for m in measure.data {
+        let measure = self.lidar.measure_pose(&self.pose, &self.frame);
+        let mut changes = Vec::new();
+        let position = self.position;
+        let absolute = |n: &ACoord| ACoord {
+            x: n.x + position.x,
+            y: n.y + position.y,
+        };
+        // the cells the beam actually crossed, not a line redrawn from the cell centre
+        for (m, crossed) in measure.data.iter().zip(&measure.crossed) {
             if let Some(n) = m {
-                let obstacle = ACoord {
-                    x: n.x + self.position.x,
-                    y: n.y + self.position.y,
-                };
-                self.planner
-                    .update(&self.environment, self.position, obstacle);
-                self.environment.insert_ray(self.position, obstacle);
-                // should check and only replan if new info
+                let crossed: Vec<ACoord> = crossed.iter().map(absolute).collect();
+                changes.extend(self.environment.insert_beam(&crossed, absolute(n)));
             }
         }
+        // only new information reaches the planner
+        if !changes.is_empty() {
+            self.planner.update(&self.environment, &changes);
+        }
     }
     pub fn action<Q: PlanIter>(&mut self, plan: Q) -> Status
         where Q: Debug
@@ -71,22 +93,48 @@ where
         }
         status
     }
+    /// Navigates to the cell containing the pose then settles onto the pose itself
+    pub fn navigate_pose(&mut self, target: &Pose) -> Status {
+        let status = self.navigate(self.frame.cell(target));
+        if status == Status::Complete {
+            self.pose = self.pose.towards(target.x, target.y);
+            self.pose.heading = target.heading;
+        }
+        status
+    }
+    /// Slides the pose straight towards the centre of the target cell
+    ///
+    /// The pose moves continuously along the segment, each cell it crosses is
+    /// checked against the map when entered and scanned from midway across it.
     pub fn control(&mut self, tgt: ACoord) -> Status
     {
         // beliefs not recorded are assumed unknown
-        // handles simulation compass rose signals
-        let mut pos = self.position;
-        let (dy, dx) = (tgt.y - pos.y, tgt.x - pos.x);
-        let (del_y, del_x) = (dy.signum(), dx.signum());
-        while !self.environment.obstructed(pos) {
-            self.position = pos;
-            self.scan();
-            if self.environment.encode(tgt) == self.environment.encode(self.position) {
-            // if tgt == self.position {
-                return Status::Enroute
+        let start = self.frame.to_map(&self.pose);
+        let goal = start.towards(tgt.x as f64 + 0.5, tgt.y as f64 + 0.5);
+        let travel = (goal.x - start.x, goal.y - start.y);
+        let crossed: Vec<(ACoord, f64)> = Traversal::new((start.x, start.y), travel)
+            .skip(1)
+            .take_while(|&(_, t)| t <= 1.0)
+            .collect();
+        for (i, &(cell, entry)) in crossed.iter().enumerate() {
+            if self.environment.obstructed(cell) {
+                // settle in the cell held so replanning starts from its centre
+                let heading = self.pose.heading;
+                self.pose = Pose { heading, ..self.frame.centre(self.position) };
+                return Status::Blocked;
             }
-            pos.x += del_x;
-            pos.y += del_y;
+            // entry points sit on borders, the middle of the span is inside the cell
+            let exit = crossed.get(i + 1).map_or(1.0, |&(_, t)| t);
+            let t = (entry + exit) / 2.0;
+            let within = Pose::new(start.x + travel.0 * t, start.y + travel.1 * t, goal.heading);
+            self.pose = self.frame.to_world(&within);
+            self.position = cell;
+            self.scan();
+        }
+        // the rest of the segment lies within the target cell
+        self.pose = self.frame.to_world(&goal);
+        if self.environment.encode(tgt) == self.environment.encode(self.position) {
+            return Status::Enroute
         }
         Status::Blocked
     }
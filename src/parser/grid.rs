@@ -1,5 +1,5 @@
 use crate::environment::grid::Grid;
-use crate::global::types::{ACoord, Belief, Bounds};
+use crate::global::types::{ACoord, Belief, Bounds, Connectivity};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fs;
@@ -49,5 +49,6 @@ pub fn read_grid(path: &str) -> Result<Grid, Box<dyn Error>> {
     Ok(Grid {
         information,
         bounds,
+        connectivity: Connectivity::Four,
     })
 }
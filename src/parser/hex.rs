@@ -0,0 +1,44 @@
+use crate::environment::hex::{HexGrid, offset_to_axial};
+use crate::global::types::Belief;
+use std::error::Error;
+use std::fs;
+
+/// Reads a hex map with odd rows shifted right, mirrored so the last line is row 0
+///
+/// Lines may be indented to stagger the cells, the indentation is ignored.
+pub fn read_hex(path: &str) -> Result<HexGrid, Box<dyn Error>> {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("Unable to read path {path:?}\n{e:?}").into()),
+    };
+    let mut max_y = 0;
+    let mut mirrored_objects = Vec::new();
+    for (idx_y, line) in content.lines().enumerate() {
+        for (idx_x, cell) in line.trim_start().as_bytes().chunks_exact(3).enumerate() {
+            let obj = match cell[1] {
+                b' ' => continue,
+                b'?' => Belief::Unknown,
+                b'#' => Belief::Occupied,
+                b'+' => Belief::Occupied,
+                b'*' => Belief::Occupied,
+                b'x' => Belief::Occupied,
+                _ => {
+                    return Err(
+                        format!("Unexpected symbol found in map with source {path:?}").into(),
+                    );
+                }
+            };
+            mirrored_objects.push(((idx_x, idx_y), obj));
+        }
+        max_y = max_y.max(idx_y);
+    }
+    let mut hex = HexGrid::new();
+    for ((idx_x, mir_idx_y), obj) in mirrored_objects {
+        let coord = offset_to_axial(idx_x as isize, (max_y - mir_idx_y) as isize);
+        hex.update_bounds(coord);
+        hex.information.insert(coord, obj);
+    }
+    hex.bounds.min_y = 0;
+    hex.bounds.max_y = max_y as isize;
+    Ok(hex)
+}
@@ -0,0 +1,45 @@
+use crate::environment::octree::Octree;
+use crate::global::types::{Belief, VCoord};
+use std::error::Error;
+use std::fs;
+
+/// Reads layers separated by blank lines, the first layer is the ground z = 0
+///
+/// Every layer is mirrored around the y axis like the planar maps, voxels
+/// not described by any layer remain unknown.
+pub fn read_layers(path: &str, levels: usize) -> Result<Octree, Box<dyn Error>> {
+    let content = match fs::read_to_string(path) {
+        Ok(c) => c,
+        Err(e) => return Err(format!("Unable to read path {path:?}\n{e:?}").into()),
+    };
+    let mut octree = Octree::init(levels);
+    for (idx_z, layer) in content.split("\n\n").enumerate() {
+        let lines: Vec<&str> = layer.lines().filter(|l| !l.is_empty()).collect();
+        let max_y = lines.len().saturating_sub(1);
+        for (mir_idx_y, line) in lines.iter().enumerate() {
+            for (idx_x, cell) in line.as_bytes().chunks_exact(3).enumerate() {
+                let obj = match cell[1] {
+                    b' ' => Belief::Free,
+                    b'?' => Belief::Unknown,
+                    b'+' => Belief::Occupied,
+                    b'*' => Belief::Occupied,
+                    b'#' => Belief::Occupied,
+                    b'x' => Belief::Occupied,
+                    _ => {
+                        return Err(
+                            format!("Unexpected symbol found in map with source {path:?}").into(),
+                        );
+                    }
+                };
+                // mapping is easiest to think of as direct representation ie mirrored b/c of parsing
+                let coord = VCoord {
+                    x: idx_x as isize,
+                    y: (max_y - mir_idx_y) as isize,
+                    z: idx_z as isize,
+                };
+                octree.update_belief(coord, obj);
+            }
+        }
+    }
+    Ok(octree)
+}
@@ -1,2 +1,4 @@
 pub mod grid;
+pub mod hex;
+pub mod layered;
 pub mod quad;
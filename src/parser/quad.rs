@@ -31,27 +31,25 @@ pub fn read_quad(path: &str, levels: usize) -> Result<QuadTree, Box<dyn Error>>
         }
         max_y = max_y.max(idx_y);
     }
-    let information = HashMap::new();
     let bounds = Bounds {
         min_x: 0,
         min_y: 0,
         max_x: max_x as isize,
         max_y: max_y as isize,
     };
-    let mut quadtree = QuadTree {
-        information,
-        bounds,
-        levels,
-    };
     // mapping is easiest to think of as direct representation ie mirrored b/c of parsing
+    let mut cells = HashMap::new();
     for ((idx_x, mir_idx_y), obj) in mirrored_objects {
-        quadtree.update_belief(
-            &ACoord {
-                x: idx_x as isize,
-                y: (max_y - mir_idx_y) as isize,
-            },
-            obj,
-        );
+        let coord = ACoord {
+            x: idx_x as isize,
+            y: (max_y - mir_idx_y) as isize,
+        };
+        cells.insert(coord, obj);
     }
-    Ok(quadtree)
+    Ok(QuadTree::from_fn(levels, &bounds, |coord| {
+        match cells.get(&coord) {
+            Some(&belief) => belief,
+            None => Belief::Unknown,
+        }
+    }))
 }
@@ -1,7 +1,8 @@
 use crate::environment::grid::Grid;
-use crate::global::types::{ACoord, CARDINALS, COMPASS_ROSE};
+use crate::global::line::Traversal;
+use crate::global::types::{ACoord, CARDINALS, COMPASS_ROSE, Frame, Opacity, Pose, SpatialMap};
 
-//TODO: Next session: orientation + beam rotation OR frontier-based exploration
+//TODO: Next session: frontier-based exploration
 //TODO: When finally make quadtree, create a hazard like cost a hazard will be used as a cost
 //gradient. essentially like when detect object propogate like danger upward, this way the planning
 //algorithm will prefer a more racing line and will be easier for sabrina to navigate effectively,
@@ -10,26 +11,98 @@ use crate::global::types::{ACoord, CARDINALS, COMPASS_ROSE};
 // smoothness
 
 const GRAIN: usize = 8;
-// Sees in 4 principle components
-pub struct Lidar {
+// Sees along its beams, compass rose by default
+pub struct Lidar<O = Grid> {
     // Max range ould be noise informed
     pub max_range: usize,
-    oracle: Grid,
+    oracle: O,
+    beams: &'static [ACoord],
+    // beams turn with the heading and leave from the exact pose over square cells
+    continuous: bool,
 }
 pub struct Measurement {
     // closest objects eventually need to refactor with theta
     pub data: [Option<ACoord>; GRAIN],
+    // cells each beam entered before its hit or its range, in order
+    pub crossed: [Vec<ACoord>; GRAIN],
 }
-impl Lidar {
-    pub fn new(max_range: usize, oracle: Grid) -> Self {
-        Self { max_range, oracle }
+impl<O: SpatialMap> Lidar<O> {
+    pub fn new(max_range: usize, oracle: O) -> Self {
+        Self {
+            continuous: true,
+            ..Self::with_beams(max_range, oracle, &COMPASS_ROSE)
+        }
+    }
+    /// Lidar casting along lattice directions of the map, ie HEX_DIRECTIONS for hex maps
+    ///
+    /// The beams follow the lines of the map from the cell holding the pose
+    /// and keep their directions whatever the heading.
+    pub fn with_beams(max_range: usize, oracle: O, beams: &'static [ACoord]) -> Self {
+        assert!(beams.len() <= GRAIN, "lidar supports at most {GRAIN} beams");
+        Self {
+            max_range,
+            oracle,
+            beams,
+            continuous: false,
+        }
     }
     pub fn measure(&self, position: ACoord) -> Measurement {
         let mut data = [None; GRAIN];
+        let mut crossed: [Vec<ACoord>; GRAIN] = Default::default();
         // polar order of scan ie counter-clockwise
-        for (h, &d) in COMPASS_ROSE.iter().enumerate() {
-            data[h] = self.oracle.raycast(position, d, self.max_range);
+        for (h, &d) in self.beams.iter().enumerate() {
+            // oracle is absolute the measurement is relative
+            data[h] = self
+                .oracle
+                .raycast(position, d, self.max_range, Opacity::Transparent)
+                .map(|hit| ACoord {
+                    x: hit.x - position.x,
+                    y: hit.y - position.y,
+                });
+            // the hit lies a whole number of steps along the beam
+            let steps = match data[h] {
+                Some(hit) if d.x != 0 => hit.x / d.x,
+                Some(hit) => hit.y / d.y,
+                None => self.max_range as isize + 1,
+            };
+            crossed[h] = (1..steps).map(|k| ACoord { x: k * d.x, y: k * d.y }).collect();
+        }
+        Measurement { data, crossed }
+    }
+    /// Measures from the continuous pose, hits relative to the cell containing it
+    ///
+    /// Beams are turned by the heading of the pose within the map and cast
+    /// from its exact position over square cells, the first beam looking
+    /// straight ahead. A range counts the cells entered along a beam.
+    pub fn measure_pose(&self, pose: &Pose, frame: &Frame) -> Measurement {
+        let position = frame.cell(pose);
+        if !self.continuous {
+            return self.measure(position);
+        }
+        let local = frame.to_map(pose);
+        let (sin, cos) = local.heading.sin_cos();
+        let mut data = [None; GRAIN];
+        let mut crossed: [Vec<ACoord>; GRAIN] = Default::default();
+        let relative = |cell: ACoord| ACoord {
+            x: cell.x - position.x,
+            y: cell.y - position.y,
+        };
+        for (h, &d) in self.beams.iter().enumerate() {
+            let (dx, dy) = (d.x as f64, d.y as f64);
+            let direction = (dx * cos - dy * sin, dx * sin + dy * cos);
+            let cells = Traversal::new((local.x, local.y), direction)
+                .skip(1)
+                .take(self.max_range)
+                .map(|(cell, _)| cell);
+            for cell in cells {
+                let belief = self.oracle.belief(self.oracle.encode(cell));
+                if belief.blocks(Opacity::Transparent) {
+                    data[h] = Some(relative(cell));
+                    break;
+                }
+                crossed[h].push(relative(cell));
+            }
         }
-        Measurement { data }
+        Measurement { data, crossed }
     }
 }
@@ -0,0 +1,33 @@
+use crate::environment::octree::Octree;
+use crate::global::types::{Opacity, VCoord, VOXEL_ROSE, VolumetricMap};
+
+const GRAIN: usize = 26;
+// Sees along the voxel rose, faces, edges and corners
+pub struct Lidar3<O = Octree> {
+    pub max_range: usize,
+    oracle: O,
+}
+pub struct Measurement3 {
+    // closest objects relative to the position, ordered as the voxel rose
+    pub data: [Option<VCoord>; GRAIN],
+}
+impl<O: VolumetricMap> Lidar3<O> {
+    pub fn new(max_range: usize, oracle: O) -> Self {
+        Self { max_range, oracle }
+    }
+    pub fn measure(&self, position: VCoord) -> Measurement3 {
+        let mut data = [None; GRAIN];
+        for (h, &d) in VOXEL_ROSE.iter().enumerate() {
+            // oracle is absolute the measurement is relative
+            data[h] = self
+                .oracle
+                .raycast(position, d, self.max_range, Opacity::Transparent)
+                .map(|hit| VCoord {
+                    x: hit.x - position.x,
+                    y: hit.y - position.y,
+                    z: hit.z - position.z,
+                });
+        }
+        Measurement3 { data }
+    }
+}
@@ -1 +1,2 @@
 pub mod lidar;
+pub mod lidar3;
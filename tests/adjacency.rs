@@ -0,0 +1,123 @@
+use sabrina::environment::quad::QuadTree;
+use sabrina::global::types::{ACoord, Belief, Bounds, HCoord, SpatialMap};
+use sabrina::hierarchy::proximity::edge_neighbors;
+use sabrina::parser::quad::read_quad;
+use std::time::Instant;
+
+fn sorted(mut nodes: Vec<HCoord>) -> Vec<HCoord> {
+    nodes.sort_by_key(|n| (n.l, n.x, n.y));
+    nodes
+}
+
+/// Every leaf's maintained adjacency agrees with the recomputed neighbours
+fn assert_linked(quad: &QuadTree) {
+    assert!(quad.adjacency.keys().all(|n| quad.information.contains_key(n)));
+    for &node in quad.information.keys() {
+        assert_eq!(
+            sorted(quad.neighbors(node)),
+            sorted(edge_neighbors(quad, node)),
+            "adjacency of {node:?} is stale"
+        );
+    }
+}
+
+#[test]
+fn test_adjacency_updates() {
+    let mut quad = QuadTree::init(4);
+    assert_linked(&quad);
+    for (x, y, belief) in [
+        (3, 3, Belief::Occupied),
+        (4, 3, Belief::Free),
+        (9, 2, Belief::Occupied),
+        (-2, 5, Belief::Free),
+        (3, 3, Belief::Free),
+        (0, 0, Belief::Free),
+    ] {
+        quad.update_belief(&ACoord { x, y }, belief);
+        assert_linked(&quad);
+    }
+    quad.insert_ray(ACoord { x: 0, y: 0 }, ACoord { x: 6, y: 6 });
+    assert_linked(&quad);
+    let rect = Bounds {
+        min_x: -3,
+        min_y: 1,
+        max_x: 12,
+        max_y: 4,
+    };
+    quad.fill_rect(&rect, Belief::Free);
+    assert_linked(&quad);
+    quad.fill_rect(&rect, Belief::Occupied);
+    assert_linked(&quad);
+}
+
+#[test]
+fn test_adjacency_parsed() {
+    let mut quad = read_quad("./data/sample/test_nav0.map", 4).unwrap();
+    assert_linked(&quad);
+    quad.insert_ray(ACoord { x: 1, y: 1 }, ACoord { x: 1, y: 6 });
+    quad.initialize(ACoord { x: 1, y: 1 }, ACoord { x: 30, y: 20 });
+    assert_linked(&quad);
+}
+
+/// Tree split into many small leaves by scattered obstacles
+fn scattered() -> QuadTree {
+    let side = 256;
+    let bounds = Bounds {
+        min_x: 0,
+        min_y: 0,
+        max_x: side - 1,
+        max_y: side - 1,
+    };
+    QuadTree::from_fn(9, &bounds, |c| {
+        if (c.x * 7919 + c.y * 104729) % 7 == 0 {
+            Belief::Occupied
+        } else {
+            Belief::Free
+        }
+    })
+}
+
+#[test]
+fn test_adjacency_scattered() {
+    let quad = scattered();
+    for &n in quad.information.keys() {
+        assert_eq!(sorted(quad.neighbors(n)), sorted(edge_neighbors(&quad, n)), "{n:?}");
+    }
+}
+
+#[test]
+#[ignore = "benchmark, timings depend on the machine"]
+fn test_adjacency_faster() {
+    let quad = scattered();
+    let leaves: Vec<HCoord> = quad.information.keys().copied().collect();
+    // best of a few rounds keeps a busy machine from deciding the race
+    let fastest = |f: &dyn Fn(HCoord) -> Vec<HCoord>| {
+        (0..3)
+            .map(|_| {
+                let start = Instant::now();
+                let count: usize = leaves.iter().map(|&n| f(n).len()).sum();
+                (start.elapsed(), count)
+            })
+            .min()
+            .unwrap()
+    };
+    let (linked_time, linked) = fastest(&|n| quad.neighbors(n));
+    let (recomputed_time, recomputed) = fastest(&|n| edge_neighbors(&quad, n));
+    assert_eq!(linked, recomputed);
+    assert!(
+        linked_time < recomputed_time,
+        "{} leaves: {linked_time:?} linked, {recomputed_time:?} recomputed",
+        leaves.len()
+    );
+}
+
+#[test]
+fn test_adjacency_stale() {
+    let mut quad = read_quad("./data/sample/test_nav0.map", 4).unwrap();
+    // leaves edited directly leave the adjacency behind until relinked
+    let node = quad.encode(ACoord { x: 1, y: 1 });
+    quad.information.remove(&node);
+    for &n in quad.information.keys() {
+        assert!(!quad.neighbors(n).contains(&node));
+    }
+}
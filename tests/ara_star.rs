@@ -0,0 +1,170 @@
+use sabrina::algo::ara_star::{AraStarPlanner, Budget, MIN_DECREMENT};
+use sabrina::environment::grid::Grid;
+use sabrina::global::types::plan::{CellChange, Planner, Provenance};
+use sabrina::global::types::{ACoord, Belief, Connectivity, PlanIter};
+use std::time::Duration;
+
+mod common;
+use common::{arena, optimal, walk};
+
+const SIDE: isize = 40;
+
+/// Walled arena with scattered obstacles, the corners kept free
+fn cornered(seed: u64, connectivity: Connectivity) -> Grid {
+    let mut grid = arena(&mut seed.clone(), SIDE, connectivity, (3, 10));
+    for coord in [ACoord { x: 1, y: 1 }, ACoord { x: SIDE - 2, y: SIDE - 2 }] {
+        grid.information.insert(coord, Belief::Free);
+    }
+    grid
+}
+
+#[test]
+fn test_unlimited_is_optimal() {
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: SIDE - 2, y: SIDE - 2 });
+    for connectivity in [Connectivity::Four, Connectivity::Octile] {
+        for seed in 0..10 {
+            let grid = cornered(seed, connectivity);
+            let mut ara = AraStarPlanner::new().with_epsilon(3.0);
+            let plan = ara.plan(&grid, source, target);
+            let found = plan.as_ref().map(|p| walk(&grid, source, p));
+            assert_eq!(found, optimal(&grid, source, target), "{connectivity:?} seed {seed}");
+            if let Some(plan) = plan {
+                assert_eq!(plan.bound.unwrap(), 1.0);
+                assert_eq!(plan.nodes().last(), Some(&target));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_budget_improves_bound() {
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: SIDE - 2, y: SIDE - 2 });
+    let mut solved = 0;
+    for seed in 0..10 {
+        let grid = cornered(seed, Connectivity::Octile);
+        let Some(best) = optimal(&grid, source, target) else {
+            continue;
+        };
+        solved += 1;
+        let mut ara = AraStarPlanner::new()
+            .with_epsilon(3.0)
+            .with_budget(Budget::expansions(20));
+        let mut bound = f64::INFINITY;
+        let mut calls = 0;
+        while bound > 1.0 {
+            calls += 1;
+            assert!(calls < 1000, "seed {seed}");
+            // the budget may run out before the first plan, the next call resumes
+            let Some(plan) = ara.plan(&grid, source, target) else {
+                continue;
+            };
+            let c = walk(&grid, source, &plan);
+            assert!(plan.bound.unwrap() <= bound, "seed {seed}");
+            assert!(c as f64 <= plan.bound.unwrap() * best as f64, "seed {seed} {c} {best}");
+            bound = plan.bound.unwrap();
+        }
+        assert!(calls > 1, "seed {seed}");
+        let plan = ara.plan(&grid, source, target).unwrap();
+        assert_eq!(walk(&grid, source, &plan), best);
+    }
+    assert!(solved > 0);
+}
+
+#[test]
+fn test_budget_before_first_plan() {
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: SIDE - 2, y: SIDE - 2 });
+    for seed in 0..10 {
+        let grid = cornered(seed, Connectivity::Octile);
+        let Some(best) = optimal(&grid, source, target) else {
+            continue;
+        };
+        let mut ara = AraStarPlanner::new()
+            .with_epsilon(3.0)
+            .with_budget(Budget::expansions(5));
+        let mut empty = 0;
+        // each call stops after five expansions, only resuming the search reaches the source
+        let plan = loop {
+            if let Some(plan) = ara.plan(&grid, source, target) {
+                break plan;
+            }
+            empty += 1;
+            assert!(empty < 1000, "seed {seed}");
+        };
+        assert!(empty > 0, "seed {seed}");
+        assert_eq!(plan.stats.provenance, Provenance::Repaired);
+        assert!(plan.stats.expanded <= 5, "seed {seed}");
+        let c = walk(&grid, source, &plan);
+        assert!(c as f64 <= plan.bound.unwrap() * best as f64, "seed {seed} {c} {best}");
+    }
+}
+
+#[test]
+fn test_moving_source() {
+    let target = ACoord { x: SIDE - 2, y: SIDE - 2 };
+    for seed in 0..10 {
+        let grid = cornered(seed, Connectivity::Octile);
+        let mut ara = AraStarPlanner::new()
+            .with_epsilon(2.0)
+            .with_budget(Budget::expansions(50));
+        let mut source = ACoord { x: 1, y: 1 };
+        while source != target {
+            let Some(best) = optimal(&grid, source, target) else {
+                assert!(ara.plan(&grid, source, target).is_none());
+                break;
+            };
+            // out of budget before the first plan, the next call resumes
+            let Some(plan) = ara.plan(&grid, source, target) else {
+                continue;
+            };
+            let c = walk(&grid, source, &plan);
+            assert!(c as f64 <= plan.bound.unwrap() * best as f64, "seed {seed} {source:?}");
+            source = *plan.iter().next().unwrap();
+        }
+    }
+}
+
+#[test]
+fn test_time_budget_and_updates() {
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: SIDE - 2, y: SIDE - 2 });
+    let mut grid = cornered(3, Connectivity::Octile);
+    let mut ara = AraStarPlanner::new().with_budget(Budget::time(Duration::ZERO));
+    assert!(ara.plan(&grid, source, target).is_none());
+    ara.budget = Budget::expansions(1);
+    let plan = loop {
+        if let Some(plan) = ara.plan(&grid, source, target) {
+            break plan;
+        }
+    };
+    assert!(plan.bound.unwrap() >= 1.0);
+    // blocking a cell on the plan discards the search
+    let coord = plan.nodes()[plan.nodes().len() / 2];
+    grid.information.insert(coord, Belief::Occupied);
+    let change = CellChange {
+        coord,
+        old: Belief::Free,
+        new: Belief::Occupied,
+    };
+    ara.update(&grid, &[change]);
+    ara.budget = Budget::unlimited();
+    let found = ara.plan(&grid, source, target).map(|p| walk(&grid, source, &p));
+    assert_eq!(found, optimal(&grid, source, target));
+}
+
+#[test]
+fn test_decrement_never_stalls() {
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: SIDE - 2, y: SIDE - 2 });
+    let grid = cornered(2, Connectivity::Octile);
+    let best = optimal(&grid, source, target);
+    for decrement in [0.0, -1.0, f64::NAN] {
+        let mut ara = AraStarPlanner::new().with_epsilon(3.0).with_decrement(decrement);
+        assert_eq!(ara.decrement, MIN_DECREMENT);
+        let plan = ara.plan(&grid, source, target);
+        assert_eq!(plan.as_ref().map(|p| walk(&grid, source, p)), best, "{decrement}");
+    }
+    // set on the field the decrement is not clamped, planning still stops
+    let mut ara = AraStarPlanner::new().with_epsilon(3.0);
+    ara.decrement = 0.0;
+    let plan = ara.plan(&grid, source, target).unwrap();
+    assert_eq!(plan.nodes().last(), Some(&target));
+    assert!(plan.bound.unwrap() <= 3.0);
+}
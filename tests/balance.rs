@@ -0,0 +1,105 @@
+use sabrina::environment::quad::QuadTree;
+use sabrina::environment::quad_validate::Violation;
+use sabrina::global::types::{ACoord, Belief, Bounds, SpatialMap};
+use sabrina::hierarchy::encoding::child_hier;
+use sabrina::hierarchy::proximity::edge_leaves;
+use sabrina::parser::grid::read_grid;
+
+/// Every cell keeps the belief of the compressed tree
+fn same_beliefs(balanced: &QuadTree, compressed: &QuadTree) {
+    for leaf in compressed.leaves() {
+        for x in leaf.bounds.min_x..=leaf.bounds.max_x {
+            for y in leaf.bounds.min_y..=leaf.bounds.max_y {
+                let coord = ACoord { x, y };
+                assert_eq!(balanced.get_coord(coord).map(|(_, b)| b), Some(leaf.belief));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_balance() {
+    for name in ["test_nav0", "test_nav1", "test_quad0", "test_quad1"] {
+        let grid = read_grid(&format!("./data/sample/{name}.map")).unwrap();
+        for levels in 1..=5 {
+            let compressed = QuadTree::from_grid(&grid, levels);
+            let balanced = compressed.clone().with_balance();
+            let stats = balanced.validate().unwrap();
+            assert!(stats.leaves >= compressed.information.len());
+            assert_eq!(stats.cells, compressed.stats().cells);
+            same_beliefs(&balanced, &compressed);
+            for leaf in balanced.leaves() {
+                // at most two leaves along each edge
+                assert!(edge_leaves(&balanced, leaf.node).len() <= 8, "{name} {levels}");
+            }
+            // incremental updates settle on the same tree as bulk construction
+            let mut incremental = QuadTree::init(levels).with_balance();
+            for x in grid.bounds.min_x..=grid.bounds.max_x {
+                for y in grid.bounds.min_y..=grid.bounds.max_y {
+                    let coord = ACoord { x, y };
+                    incremental.update_belief(&coord, SpatialMap::belief(&grid, coord));
+                }
+            }
+            assert_eq!(incremental.information, balanced.information, "{name} {levels}");
+        }
+    }
+}
+
+#[test]
+fn test_balance_updates() {
+    let mut balanced = QuadTree::init(5).with_balance();
+    let mut compressed = QuadTree::init(5);
+    let mut state: u64 = 11;
+    for step in 0..300 {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let coord = ACoord {
+            x: (state >> 33) as isize % 20 - 2,
+            y: (state >> 45) as isize % 20 - 2,
+        };
+        let belief = [Belief::Free, Belief::Occupied][(state >> 20) as usize % 2];
+        if step % 25 == 0 {
+            let rect = Bounds {
+                min_x: coord.x,
+                min_y: coord.y,
+                max_x: coord.x + 5,
+                max_y: coord.y + 3,
+            };
+            balanced.fill_rect(&rect, belief);
+            compressed.fill_rect(&rect, belief);
+        } else {
+            balanced.update_belief(&coord, belief);
+            compressed.update_belief(&coord, belief);
+        }
+        assert!(balanced.validate().is_ok(), "{step}");
+    }
+    same_beliefs(&balanced, &compressed);
+    // clearing everything coarsens back to the top level blocks
+    let everything = Bounds {
+        min_x: -16,
+        min_y: -16,
+        max_x: 31,
+        max_y: 31,
+    };
+    balanced.fill_rect(&everything, Belief::Free);
+    compressed.fill_rect(&everything, Belief::Free);
+    assert_eq!(balanced.information, compressed.information);
+}
+
+#[test]
+fn test_balanced_unmerged() {
+    let mut balanced = QuadTree::init(4).with_balance();
+    balanced.update_belief(&ACoord { x: 1, y: 1 }, Belief::Occupied);
+    assert!(balanced.validate().is_ok());
+    // a leaf the balance kept whole is split into siblings sharing its belief
+    for leaf in balanced.leaves().filter(|l| l.node.l > 0) {
+        let mut split = balanced.clone();
+        let qnode = split.information.remove(&leaf.node).unwrap();
+        let children = child_hier(&leaf.node);
+        for child in children {
+            split.information.insert(child, qnode);
+        }
+        split.relink_nodes(children.into_iter().chain([leaf.node]).collect());
+        let violations = split.validate().unwrap_err();
+        assert!(violations.contains(&Violation::Unmerged { parent: leaf.node }), "{leaf:?}");
+    }
+}
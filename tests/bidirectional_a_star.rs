@@ -0,0 +1,132 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::algo::bidirectional_a_star::BidirectionalAStarPlanner;
+use sabrina::environment::grid::Grid;
+use sabrina::environment::hex::offset_to_axial;
+use sabrina::environment::quad::QuadTree;
+use sabrina::global::types::plan::Planner;
+use sabrina::global::types::{ACoord, Belief, Bounds, Connectivity, PlanIter, SpatialMap};
+use sabrina::parser::grid::read_grid;
+use sabrina::parser::hex::read_hex;
+
+mod common;
+use common::{arena, interior, optimal, walk};
+
+const SIDE: isize = 24;
+
+#[test]
+fn test_grid_optimality() {
+    for connectivity in [Connectivity::Four, Connectivity::Octile, Connectivity::Euclidean] {
+        for seed in 0..40 {
+            let mut state = seed;
+            let mut grid = arena(&mut state, SIDE, connectivity, (3, 10));
+            let (source, target) = (interior(&mut state, SIDE), interior(&mut state, SIDE));
+            grid.information.insert(source, Belief::Free);
+            grid.information.insert(target, Belief::Free);
+            let plan = BidirectionalAStarPlanner.plan(&grid, source, target);
+            let found = plan.as_ref().map(|p| walk(&grid, source, p));
+            assert_eq!(found, optimal(&grid, source, target), "{connectivity:?} seed {seed}");
+            if let Some(plan) = plan.filter(|_| source != target) {
+                assert_eq!(plan.nodes().last(), Some(&target));
+            }
+        }
+    }
+}
+
+#[test]
+fn test_quad_optimality() {
+    for seed in 0..20 {
+        let mut state = seed;
+        let grid = arena(&mut state, SIDE, Connectivity::Octile, (3, 10));
+        let quad = QuadTree::from_grid(&grid, 5);
+        let (source, target) = (interior(&mut state, SIDE), interior(&mut state, SIDE));
+        if quad.obstructed(source) {
+            continue;
+        }
+        let found = BidirectionalAStarPlanner
+            .plan(&quad, source, target)
+            .map(|p| walk(&quad, source, &p));
+        assert_eq!(found, optimal(&quad, source, target), "seed {seed}");
+    }
+}
+
+#[test]
+fn test_hex_optimality() {
+    let hex = read_hex("./data/sample/test_hex0.map").unwrap();
+    let source = offset_to_axial(1, 1);
+    for (col, row) in [(12, 7), (5, 3), (1, 1), (9, 6)] {
+        let target = offset_to_axial(col, row);
+        let found = BidirectionalAStarPlanner
+            .plan(&hex, source, target)
+            .map(|p| walk(&hex, source, &p));
+        assert_eq!(found, optimal(&hex, source, target), "{col} {row}");
+    }
+}
+
+#[test]
+fn test_same_source_and_target() {
+    let mut state = 3;
+    let grid = arena(&mut state, SIDE, Connectivity::Four, (3, 10));
+    let wall = ACoord { x: 0, y: 0 };
+    let inside = (1..SIDE - 1)
+        .flat_map(|x| (1..SIDE - 1).map(move |y| ACoord { x, y }))
+        .find(|&c| !grid.obstructed(c))
+        .unwrap();
+    let plan = BidirectionalAStarPlanner.plan(&grid, inside, inside).unwrap();
+    assert!(plan.nodes().is_empty());
+    assert!(BidirectionalAStarPlanner.plan(&grid, inside, wall).is_none());
+}
+
+#[test]
+fn test_nav_expands_less() {
+    let grid = read_grid("./data/sample/test_nav1.map").unwrap();
+    let free: Vec<ACoord> = (0..4)
+        .flat_map(|x| (0..5).map(move |y| ACoord { x, y }))
+        .filter(|&c| !grid.obstructed(c))
+        .collect();
+    for connectivity in [Connectivity::Four, Connectivity::Octile] {
+        let grid = grid.clone().with_connectivity(connectivity);
+        let (mut one, mut both) = (0, 0);
+        for &source in &free {
+            for &target in &free {
+                let forward = AStarPlanner.plan(&grid, source, target).unwrap();
+                let meet = BidirectionalAStarPlanner.plan(&grid, source, target).unwrap();
+                assert_eq!(forward.cost, meet.cost, "{source:?} {target:?}");
+                // the corridor is too short to halve, but never costs more
+                assert!(meet.stats.expanded <= forward.stats.expanded, "{source:?} {target:?}");
+                one += forward.stats.expanded;
+                both += meet.stats.expanded;
+            }
+        }
+        assert!(both < one, "{connectivity:?} {both} {one}");
+    }
+}
+
+#[test]
+fn test_dead_end_expands_less() {
+    // the target sits in a pocket whose back wall faces the source
+    let mut grid = Grid::new();
+    let field = Bounds {
+        min_x: 0,
+        min_y: 0,
+        max_x: 79,
+        max_y: 79,
+    };
+    grid.fill_region(&field, Belief::Free);
+    for y in 20..=60 {
+        grid.information.insert(ACoord { x: 60, y }, Belief::Occupied);
+    }
+    for x in 60..=63 {
+        grid.information.insert(ACoord { x, y: 20 }, Belief::Occupied);
+        grid.information.insert(ACoord { x, y: 60 }, Belief::Occupied);
+    }
+    let (source, target) = (ACoord { x: 5, y: 40 }, ACoord { x: 61, y: 40 });
+    for connectivity in [Connectivity::Four, Connectivity::Octile] {
+        let grid = grid.clone().with_connectivity(connectivity);
+        let forward = AStarPlanner.plan(&grid, source, target).unwrap();
+        let meet = BidirectionalAStarPlanner.plan(&grid, source, target).unwrap();
+        assert_eq!(forward.cost, meet.cost);
+        // forward search floods the shadow of the wall, the backward side walks out
+        let (one, both) = (forward.stats.expanded, meet.stats.expanded);
+        assert!(both * 3 < one * 2, "{connectivity:?} {both} {one}");
+    }
+}
@@ -0,0 +1,72 @@
+// each test crate uses its own subset of the fixtures
+#![allow(dead_code)]
+
+use sabrina::algo::dijkstra::DijkstraPlanner;
+use sabrina::environment::grid::Grid;
+use sabrina::global::types::{ACoord, Belief, Bounds, Connectivity, PlanIter, SpatialMap};
+
+/// Linear congruential generator, seeded fixtures stay reproducible
+pub fn next(state: &mut u64) -> u64 {
+    *state = state
+        .wrapping_mul(6364136223846793005)
+        .wrapping_add(1442695040888963407);
+    *state >> 33
+}
+
+/// Random cell inside the walls of an arena of the given side
+pub fn interior(state: &mut u64, side: isize) -> ACoord {
+    ACoord {
+        x: 1 + (next(state) % (side as u64 - 2)) as isize,
+        y: 1 + (next(state) % (side as u64 - 2)) as isize,
+    }
+}
+
+/// Walled arena with scattered obstacles, the walls keep the searches finite
+///
+/// Each interior cell is occupied with odds numerator out of denominator.
+pub fn arena(state: &mut u64, side: isize, connectivity: Connectivity, odds: (u64, u64)) -> Grid {
+    let mut grid = Grid::new().with_connectivity(connectivity);
+    let rect = Bounds {
+        min_x: 0,
+        min_y: 0,
+        max_x: side - 1,
+        max_y: side - 1,
+    };
+    grid.fill_region(&rect, Belief::Occupied);
+    for x in 1..side - 1 {
+        for y in 1..side - 1 {
+            let belief = if next(state) % odds.1 < odds.0 {
+                Belief::Occupied
+            } else {
+                Belief::Free
+            };
+            grid.information.insert(ACoord { x, y }, belief);
+        }
+    }
+    grid
+}
+
+/// Cost of walking the plan from the source, every step between free neighbours
+pub fn walk<S: SpatialMap, P: PlanIter>(env: &S, source: ACoord, plan: &P) -> usize {
+    let mut prev = env.encode(source);
+    let mut total = 0;
+    for &coord in plan.iter() {
+        let next = env.encode(coord);
+        assert_ne!(env.belief(next), Belief::Occupied, "{coord:?}");
+        assert!(next == prev || env.neighbors(prev).contains(&next), "{coord:?}");
+        total += env.distance(prev, next);
+        prev = next;
+    }
+    total
+}
+
+/// Optimal cost from the source to the target, none when either is cut off
+pub fn optimal<S: SpatialMap>(env: &S, source: ACoord, target: ACoord) -> Option<usize> {
+    if env.obstructed(target) {
+        return None;
+    }
+    DijkstraPlanner::new()
+        .with_early_exit(false)
+        .field(env, target)
+        .cost(env.encode(source))
+}
@@ -1,5 +1,8 @@
-use sabrina::environment::quad::QuadTree;
-use sabrina::global::types::{ACoord, Belief};
+use sabrina::environment::quad::{QuadNode, QuadTree};
+use sabrina::environment::quad_validate::Violation;
+use sabrina::global::types::{ACoord, Belief, Bounds, HCoord, SpatialMap};
+use sabrina::parser::grid::read_grid;
+use sabrina::parser::quad::read_quad;
 
 #[test]
 fn test_compression() {
@@ -25,3 +28,155 @@ fn test_compression() {
     assert_eq!(map.information.len(), 1);
     assert_eq!(map.get_coord(ACoord { x: 1, y: 1 }), Some((1, Belief::Free)));
 }
+
+#[test]
+fn test_fill_rect() {
+    let levels = 4;
+    let rect = Bounds {
+        min_x: 1,
+        min_y: 2,
+        max_x: 12,
+        max_y: 6,
+    };
+    let mut filled = QuadTree::init(levels);
+    filled.fill_rect(&rect, Belief::Occupied);
+    let mut reference = QuadTree::init(levels);
+    for x in rect.min_x..=rect.max_x {
+        for y in rect.min_y..=rect.max_y {
+            reference.update_belief(&ACoord { x, y }, Belief::Occupied);
+        }
+    }
+    assert_eq!(filled.information, reference.information);
+    let cells: usize = filled
+        .leaves()
+        .filter(|leaf| leaf.belief == Belief::Occupied)
+        .map(|leaf| 1 << (2 * leaf.node.l))
+        .sum();
+    assert_eq!(cells, 12 * 5);
+    filled.fill_rect(&rect, Belief::Free);
+    filled.fill_rect(
+        &Bounds {
+            min_x: 0,
+            min_y: 0,
+            max_x: 15,
+            max_y: 7,
+        },
+        Belief::Free,
+    );
+    assert_eq!(filled.information.len(), 2);
+}
+
+#[test]
+fn test_range() {
+    let mut map = QuadTree::init(3);
+    map.update_belief(&ACoord { x: 1, y: 1 }, Belief::Occupied);
+    let query = Bounds {
+        min_x: 0,
+        min_y: 0,
+        max_x: 1,
+        max_y: 1,
+    };
+    let found = map.range(&query);
+    assert_eq!(found.len(), 4);
+    assert!(found.iter().all(|leaf| leaf.node.l == 0));
+    let everything = Bounds {
+        min_x: 0,
+        min_y: 0,
+        max_x: 7,
+        max_y: 7,
+    };
+    assert_eq!(map.range(&everything).len(), map.leaves().count());
+    assert!(
+        map.range(&everything)
+            .iter()
+            .all(|leaf| everything.encloses(&leaf.bounds))
+    );
+}
+
+#[test]
+fn test_bulk_construction() {
+    for name in ["test_nav0", "test_nav1", "test_quad0", "test_quad1"] {
+        let path = format!("./data/sample/{name}.map");
+        let grid = read_grid(&path).unwrap();
+        for levels in 1..=4 {
+            let bulk = QuadTree::from_grid(&grid, levels);
+            let mut incremental = QuadTree::init(levels);
+            for x in grid.bounds.min_x..=grid.bounds.max_x {
+                for y in grid.bounds.min_y..=grid.bounds.max_y {
+                    let coord = ACoord { x, y };
+                    incremental.update_belief(&coord, SpatialMap::belief(&grid, coord));
+                }
+            }
+            assert_eq!(bulk.information, incremental.information, "{name} {levels}");
+            assert_eq!(read_quad(&path, levels).unwrap().information, bulk.information);
+            let round_trip = QuadTree::from_grid(&bulk.to_grid(), levels);
+            assert_eq!(round_trip.information, bulk.information);
+        }
+    }
+}
+
+#[test]
+fn test_validate() {
+    for name in ["test_nav0", "test_nav1", "test_quad0", "test_quad1"] {
+        let path = format!("./data/sample/{name}.map");
+        let grid = read_grid(&path).unwrap();
+        let quad = read_quad(&path, 4).unwrap();
+        let stats = quad.validate().unwrap();
+        assert_eq!(stats.leaves, quad.information.len(), "{name}");
+        assert_eq!(stats.per_level.iter().sum::<usize>(), stats.leaves);
+        assert!(stats.cells >= grid.information.len());
+        assert!(stats.compression >= 1.0);
+        assert!(stats.memory > 0);
+    }
+    // scattered updates keep the invariants after every step
+    let mut map = QuadTree::init(4);
+    let mut state: u64 = 7;
+    for _ in 0..500 {
+        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        let coord = ACoord {
+            x: (state >> 33) as isize % 10 - 1,
+            y: (state >> 45) as isize % 10 - 1,
+        };
+        let belief = [Belief::Free, Belief::Occupied, Belief::Unknown][(state >> 20) as usize % 3];
+        map.update_belief(&coord, belief);
+        assert!(map.validate().is_ok(), "{coord:?}");
+    }
+}
+
+#[test]
+fn test_violations() {
+    let leaf = QuadNode {
+        homogenous: true,
+        belief: Belief::Free,
+    };
+    let mut map = QuadTree::init(3);
+    map.update_belief(&ACoord { x: 1, y: 1 }, Belief::Occupied);
+    let quarter = HCoord { l: 1, x: 2, y: 2 };
+    let cell = HCoord { l: 0, x: 3, y: 3 };
+    let mut orphan = map.clone();
+    orphan.information.insert(cell, leaf);
+    orphan.relink_nodes(vec![cell]);
+    assert!(orphan.validate().unwrap_err().contains(&Violation::Overlap {
+        node: cell,
+        ancestor: quarter
+    }));
+    let mut gap = map.clone();
+    gap.information.remove(&quarter);
+    gap.relink_nodes(vec![quarter]);
+    assert_eq!(gap.validate(), Err(vec![Violation::Gap { node: quarter }]));
+    let mut unmerged = map.clone();
+    unmerged.information.remove(&quarter);
+    for x in 2..4 {
+        for y in 2..4 {
+            unmerged.information.insert(HCoord { l: 0, x, y }, leaf);
+        }
+    }
+    unmerged.relink_nodes(unmerged.information.keys().copied().chain([quarter]).collect());
+    assert_eq!(unmerged.validate(), Err(vec![Violation::Unmerged { parent: quarter }]));
+    let mut stale = map.clone();
+    stale.information.get_mut(&quarter).unwrap().homogenous = false;
+    stale.adjacency.remove(&quarter);
+    let violations = stale.validate().unwrap_err();
+    assert!(violations.contains(&Violation::Heterogeneous { node: quarter }));
+    assert!(violations.contains(&Violation::Adjacency { node: quarter }));
+}
@@ -0,0 +1,47 @@
+use sabrina::environment::conformance::{HEX_RAYS, check_spatial_map, check_spatial_map_with};
+use sabrina::environment::grid::Grid;
+use sabrina::environment::hex::HexGrid;
+use sabrina::environment::linear_quad::LinearQuadTree;
+use sabrina::environment::quad::QuadTree;
+use sabrina::environment::quadrant_grid::QuadrantGrid;
+use sabrina::global::types::Connectivity;
+
+#[test]
+fn test_grid_conformance() {
+    check_spatial_map(Grid::new);
+}
+
+#[test]
+fn test_quad_conformance() {
+    for levels in 1..=4 {
+        check_spatial_map(|| QuadTree::init(levels));
+        check_spatial_map(|| QuadTree::init(levels).with_balance());
+    }
+}
+
+#[test]
+fn test_quadrant_conformance() {
+    check_spatial_map(QuadrantGrid::new);
+}
+
+#[test]
+fn test_hex_conformance() {
+    check_spatial_map_with(HexGrid::new, &HEX_RAYS);
+}
+
+#[test]
+fn test_connected_conformance() {
+    for connectivity in [Connectivity::Octile, Connectivity::Euclidean] {
+        check_spatial_map(|| Grid::new().with_connectivity(connectivity));
+        for levels in 1..=4 {
+            check_spatial_map(|| QuadTree::init(levels).with_connectivity(connectivity));
+        }
+    }
+}
+
+#[test]
+fn test_linear_quad_conformance() {
+    for levels in 1..=4 {
+        check_spatial_map(|| LinearQuadTree::init(levels));
+    }
+}
@@ -0,0 +1,70 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::environment::grid::Grid;
+use sabrina::environment::quad::QuadTree;
+use sabrina::global::types::plan::Planner;
+use sabrina::global::types::{ACoord, Belief, Connectivity, PlanIter, SpatialMap, Status};
+use sabrina::intelligence::sabrina::Sabrina;
+use sabrina::parser::grid::read_grid;
+use sabrina::sensor::lidar::Lidar;
+
+#[test]
+fn test_corner_cutting() {
+    let origin = ACoord { x: 0, y: 0 };
+    let mut grid = Grid::new().with_connectivity(Connectivity::Octile);
+    assert_eq!(grid.neighbors(origin).len(), 8);
+    grid.information.insert(ACoord { x: 1, y: 0 }, Belief::Occupied);
+    let neighbors = grid.neighbors(origin);
+    assert_eq!(neighbors.len(), 5);
+    assert!(!neighbors.contains(&ACoord { x: 1, y: 1 }));
+    assert!(!neighbors.contains(&ACoord { x: 1, y: -1 }));
+}
+
+#[test]
+fn test_step_costs() {
+    let (a, b) = (ACoord { x: 0, y: 0 }, ACoord { x: 4, y: 2 });
+    assert_eq!(Connectivity::Four.cost(a, b), 6);
+    assert_eq!(Connectivity::Octile.cost(a, b), 48);
+    assert_eq!(Connectivity::Euclidean.cost(a, b), 45);
+    let mut planner = AStarPlanner;
+    let grid = Grid::new().with_connectivity(Connectivity::Octile);
+    let plan = planner.plan(&grid, a, b).unwrap();
+    // plans exclude the source they start from
+    let nodes: Vec<ACoord> = std::iter::once(a).chain(plan.iter().copied()).collect();
+    let cost: usize = nodes.windows(2).map(|w| grid.distance(w[0], w[1])).sum();
+    assert_eq!(cost, 48);
+}
+
+#[test]
+fn test_diagonal_navigation() {
+    let path = "./data/sample/test_nav0.map";
+    match read_grid(path) {
+        Ok(oracle) => {
+            let source = ACoord { x: 1, y: 1 };
+            let target = ACoord { x: 18, y: 3 };
+            let mut planner = AStarPlanner;
+            let four = planner.plan(&oracle, source, target).unwrap();
+            let octile = oracle.clone().with_connectivity(Connectivity::Octile);
+            let plan = planner.plan(&octile, source, target).unwrap();
+            let nodes: Vec<ACoord> = std::iter::once(source).chain(plan.iter().copied()).collect();
+            assert!(plan.nodes().len() <= four.nodes().len());
+            for w in nodes.windows(2) {
+                let step = ACoord {
+                    x: w[1].x - w[0].x,
+                    y: w[1].y - w[0].y,
+                };
+                assert!(!Connectivity::cuts_corner(w[0], step, |c| octile.obstructed(c)));
+            }
+            let quad = QuadTree::from_grid(&octile, 5);
+            assert!(planner.plan(&quad, source, target).is_some());
+
+            let environment = Grid::new().with_connectivity(Connectivity::Octile);
+            let lidar = Lidar::new(100, oracle.clone());
+            let mut sabby = Sabrina::new(source, environment, lidar, AStarPlanner);
+            assert_eq!(Status::Complete, sabby.navigate(target));
+            assert_eq!(sabby.position, target);
+        }
+        Err(e) => {
+            panic!("Unexpected error in diagonal navigation\n{e:?}");
+        }
+    }
+}
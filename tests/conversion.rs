@@ -0,0 +1,86 @@
+use sabrina::environment::convert::transfer;
+use sabrina::environment::grid::Grid;
+use sabrina::environment::quad::QuadTree;
+use sabrina::environment::quadrant_grid::QuadrantGrid;
+use sabrina::global::types::{ACoord, Belief, Bounds, SpatialMap};
+use sabrina::parser::grid::read_grid;
+
+fn beliefs<S: SpatialMap>(map: &S, bounds: &Bounds) -> Vec<Belief> {
+    let mut beliefs = Vec::new();
+    for x in bounds.min_x..=bounds.max_x {
+        for y in bounds.min_y..=bounds.max_y {
+            beliefs.push(map.belief(map.encode(ACoord { x, y })));
+        }
+    }
+    beliefs
+}
+
+#[test]
+fn test_representations_agree() {
+    let path = "./data/sample/test_nav1.map";
+    match read_grid(path) {
+        Ok(grid) => {
+            let bounds = grid.bounds.clone();
+            let expected = beliefs(&grid, &bounds);
+            let quad = QuadTree::from_map(&grid, 3);
+            assert_eq!(quad.information, QuadTree::from_grid(&grid, 3).information);
+            assert_eq!(beliefs(&quad, &bounds), expected);
+            let quadrant = QuadrantGrid::from_map(&quad);
+            assert_eq!(beliefs(&quadrant, &bounds), expected);
+            let round_trip = Grid::from_map(&quadrant);
+            assert_eq!(beliefs(&round_trip, &bounds), expected);
+            let compressed = QuadTree::from_map(&round_trip, 3);
+            assert_eq!(compressed.information, quad.information);
+        }
+        Err(e) => {
+            panic!("Unexpected error in conversion\n{e:?}");
+        }
+    }
+}
+
+#[test]
+fn test_negative_quadrants() {
+    let mut quad = QuadTree::init(2);
+    let rect = Bounds {
+        min_x: -3,
+        min_y: -2,
+        max_x: 2,
+        max_y: 1,
+    };
+    quad.fill_rect(&rect, Belief::Occupied);
+    let mut quadrant = QuadrantGrid::new();
+    transfer(&quad, &mut quadrant);
+    assert_eq!(beliefs(&quadrant, &rect), beliefs(&quad, &rect));
+    assert!(beliefs(&quadrant, &rect).iter().all(|&b| b == Belief::Occupied));
+}
+
+#[test]
+fn test_quadrant_axes() {
+    // the origin and the axes bordering quadrants 2 and 3, padded slots in between
+    let mut quadrant = QuadrantGrid::new();
+    let cells = [
+        (ACoord { x: 0, y: 0 }, Belief::Free),
+        (ACoord { x: 0, y: -1 }, Belief::Occupied),
+        (ACoord { x: 0, y: -3 }, Belief::Free),
+        (ACoord { x: -1, y: 0 }, Belief::Occupied),
+        (ACoord { x: -2, y: 0 }, Belief::Free),
+    ];
+    for (coord, belief) in cells {
+        let rect = Bounds {
+            min_x: coord.x,
+            min_y: coord.y,
+            max_x: coord.x,
+            max_y: coord.y,
+        };
+        quadrant.fill_region(&rect, belief);
+    }
+    let grid = Grid::from_map(&quadrant);
+    for (coord, belief) in cells {
+        assert_eq!(quadrant.belief(coord), belief, "{coord:?}");
+        assert_eq!(grid.belief(coord), belief, "{coord:?}");
+    }
+    // only written cells are exported, the padded ones stay unrecorded
+    assert_eq!(quadrant.regions().len(), cells.len());
+    assert_eq!(quadrant.belief(ACoord { x: 0, y: -2 }), Belief::Unknown);
+    assert!(!grid.information.contains_key(&ACoord { x: 0, y: -2 }));
+}
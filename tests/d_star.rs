@@ -0,0 +1,161 @@
+use sabrina::algo::d_star::DStarPlanner;
+use sabrina::environment::grid::Grid;
+use sabrina::environment::quad::QuadTree;
+use sabrina::global::types::plan::{CellChange, Planner, Provenance};
+use sabrina::global::types::{ACoord, Belief, Bounds, Connectivity, PlanIter, SpatialMap};
+
+mod common;
+use common::{arena, interior, next, optimal, walk};
+
+const SIDE: isize = 14;
+
+fn check_updates(connectivity: Connectivity, seed: u64) {
+    let mut state = seed;
+    let mut grid = arena(&mut state, SIDE, connectivity, (1, 4));
+    let (mut source, target) = (interior(&mut state, SIDE), interior(&mut state, SIDE));
+    grid.information.insert(source, Belief::Free);
+    grid.information.insert(target, Belief::Free);
+    let mut dstar = DStarPlanner::new();
+    let mut plan = dstar.plan(&grid, source, target);
+    for round in 0..40 {
+        let expected = optimal(&grid, source, target);
+        let found = plan.as_ref().map(|p| walk(&grid, source, p));
+        assert_eq!(found, expected, "{connectivity:?} seed {seed} round {round}");
+        // the robot moves along the plan every other round
+        if round % 2 == 1
+            && let Some(&step) = plan.as_ref().and_then(|p| p.iter().next())
+        {
+            source = step;
+        }
+        let mut changes = Vec::new();
+        for _ in 0..1 + next(&mut state) % 4 {
+            let coord = interior(&mut state, SIDE);
+            if coord == source || coord == target {
+                continue;
+            }
+            let old = grid.belief(coord);
+            let new = match old {
+                Belief::Occupied => Belief::Free,
+                _ => Belief::Occupied,
+            };
+            grid.information.insert(coord, new);
+            changes.push(CellChange { coord, old, new });
+        }
+        dstar.update(&grid, &changes);
+        plan = dstar.plan(&grid, source, target);
+    }
+}
+
+#[test]
+fn test_updates_four() {
+    for seed in 0..25 {
+        check_updates(Connectivity::Four, seed);
+    }
+}
+
+#[test]
+fn test_updates_octile() {
+    for seed in 0..25 {
+        check_updates(Connectivity::Octile, seed);
+    }
+}
+
+#[test]
+fn test_updates_euclidean() {
+    for seed in 0..10 {
+        check_updates(Connectivity::Euclidean, seed);
+    }
+}
+
+/// Flips cells of the quadtree, merging and splitting the nodes the search holds
+fn check_quad_updates(seed: u64) {
+    let mut state = seed;
+    let grid = arena(&mut state, SIDE, Connectivity::Octile, (1, 4));
+    let mut quad = QuadTree::from_grid(&grid, 4);
+    let (mut source, target) = (interior(&mut state, SIDE), interior(&mut state, SIDE));
+    for coord in [source, target] {
+        quad.update_belief(&coord, Belief::Free);
+    }
+    let mut dstar = DStarPlanner::new();
+    let mut plan = dstar.plan(&quad, source, target);
+    for round in 0..40 {
+        let expected = optimal(&quad, source, target);
+        assert_eq!(plan.as_ref().map(|p| p.cost), expected, "seed {seed} round {round}");
+        if round % 2 == 1
+            && let Some(&step) = plan.as_ref().and_then(|p| p.iter().next())
+        {
+            source = step;
+        }
+        let node = quad.encode(target);
+        let mut changes = Vec::new();
+        for _ in 0..1 + next(&mut state) % 4 {
+            let coord = interior(&mut state, SIDE);
+            if coord == source || coord == target {
+                continue;
+            }
+            let old = quad.belief(quad.encode(coord));
+            let new = match old {
+                Belief::Occupied => Belief::Free,
+                _ => Belief::Occupied,
+            };
+            quad.update_belief(&coord, new);
+            changes.push(CellChange { coord, old, new });
+        }
+        dstar.update(&quad, &changes);
+        plan = dstar.plan(&quad, source, target);
+        // only a target merged or split away starts the search over
+        if let Some(p) = plan.as_ref().filter(|_| quad.encode(target) == node) {
+            assert_eq!(p.stats.provenance, Provenance::Repaired, "seed {seed} round {round}");
+        }
+    }
+}
+
+#[test]
+fn test_updates_quad() {
+    for seed in 0..25 {
+        check_quad_updates(seed);
+    }
+}
+
+#[test]
+fn test_cost_decrease() {
+    // a wall with a single gap far down, a shorter gap opens before both close
+    let mut grid = Grid::new().with_connectivity(Connectivity::Octile);
+    let rect = Bounds {
+        min_x: 0,
+        min_y: 0,
+        max_x: SIDE - 1,
+        max_y: SIDE - 1,
+    };
+    grid.fill_region(&rect, Belief::Occupied);
+    let inner = Bounds {
+        min_x: 1,
+        min_y: 1,
+        max_x: SIDE - 2,
+        max_y: SIDE - 2,
+    };
+    grid.fill_region(&inner, Belief::Free);
+    let wall = Bounds {
+        min_x: 7,
+        min_y: 1,
+        max_x: 7,
+        max_y: SIDE - 3,
+    };
+    grid.fill_region(&wall, Belief::Occupied);
+    let (source, target) = (ACoord { x: 2, y: 2 }, ACoord { x: 12, y: 2 });
+    let mut dstar = DStarPlanner::new();
+    let detour = dstar.plan(&grid, source, target).unwrap();
+    assert_eq!(Some(walk(&grid, source, &detour)), optimal(&grid, source, target));
+    let mut open = |grid: &mut Grid, coord: ACoord, new: Belief| {
+        let old = grid.belief(coord);
+        grid.information.insert(coord, new);
+        dstar.update(grid, &[CellChange { coord, old, new }]);
+        dstar.plan(grid, source, target)
+    };
+    let shortcut = open(&mut grid, ACoord { x: 7, y: 2 }, Belief::Free).unwrap();
+    assert!(walk(&grid, source, &shortcut) < walk(&grid, source, &detour));
+    assert_eq!(Some(walk(&grid, source, &shortcut)), optimal(&grid, source, target));
+    let blocked = open(&mut grid, ACoord { x: 7, y: SIDE - 2 }, Belief::Occupied);
+    assert_eq!(Some(walk(&grid, source, &blocked.unwrap())), optimal(&grid, source, target));
+    assert!(open(&mut grid, ACoord { x: 7, y: 2 }, Belief::Occupied).is_none());
+}
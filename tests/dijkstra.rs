@@ -0,0 +1,93 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::algo::d_star::DStarPlanner;
+use sabrina::algo::dijkstra::DijkstraPlanner;
+use sabrina::environment::grid::Grid;
+use sabrina::environment::quad::QuadTree;
+use sabrina::global::types::plan::Planner;
+use sabrina::global::types::{ACoord, Belief, Connectivity, PlanIter, SpatialMap};
+use sabrina::parser::grid::read_grid;
+
+/// Cost of walking the plan from the source
+fn cost<S: SpatialMap, P: PlanIter>(env: &S, source: ACoord, plan: &P) -> usize {
+    let mut prev = env.encode(source);
+    let mut total = 0;
+    for &coord in plan.iter() {
+        let next = env.encode(coord);
+        total += env.distance(prev, next);
+        prev = next;
+    }
+    total
+}
+
+#[test]
+fn test_cost_field() {
+    let grid = read_grid("./data/sample/test_nav0.map").unwrap();
+    let target = ACoord { x: 18, y: 3 };
+    let field = DijkstraPlanner::new().field(&grid, target);
+    assert_eq!(field.cost(grid.encode(target)), Some(0));
+    // every settled cost is the cheapest step onto a neighbour
+    for (&node, &c) in field.costs.iter().filter(|(n, _)| **n != field.target) {
+        let best = grid
+            .neighbors(node)
+            .into_iter()
+            .filter_map(|n| field.cost(n).map(|m| m + grid.distance(node, n)))
+            .min();
+        assert_eq!(best, Some(c), "{node:?}");
+    }
+    let heatmap = field.decode(&grid);
+    assert_eq!(heatmap.len(), field.costs.len());
+    assert!(heatmap.keys().all(|&c| grid.belief(c) != Belief::Occupied));
+}
+
+#[test]
+fn test_optimality() {
+    let grid = read_grid("./data/sample/test_nav0.map").unwrap();
+    let target = ACoord { x: 18, y: 3 };
+    let field = DijkstraPlanner::new().field(&grid, target);
+    for (&node, &c) in &field.costs {
+        let source = grid.decode(node);
+        if source == target {
+            continue;
+        }
+        let astar = AStarPlanner.plan(&grid, source, target).unwrap();
+        let dstar = DStarPlanner::new().plan(&grid, source, target).unwrap();
+        let dijkstra = DijkstraPlanner::new().plan(&grid, source, target).unwrap();
+        assert_eq!(cost(&grid, source, &astar), c, "{source:?}");
+        assert_eq!(cost(&grid, source, &dstar), c, "{source:?}");
+        assert_eq!(cost(&grid, source, &dijkstra), c, "{source:?}");
+    }
+}
+
+#[test]
+fn test_radius_and_early_exit() {
+    let mut grid = read_grid("./data/sample/test_nav0.map").unwrap();
+    grid = grid.with_connectivity(Connectivity::Octile);
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+    let full = DijkstraPlanner::new().with_early_exit(false);
+    let c = full.field(&grid, target).cost(grid.encode(source)).unwrap();
+    let bounded = DijkstraPlanner::new().with_radius(c / 2);
+    assert!(bounded.field(&grid, target).costs.values().all(|&m| m <= c / 2));
+    assert!(DijkstraPlanner::new().with_radius(c - 1).plan(&grid, source, target).is_none());
+    let exact = DijkstraPlanner::new().with_radius(c).plan(&grid, source, target).unwrap();
+    let eager = DijkstraPlanner::new().plan(&grid, source, target).unwrap();
+    let exhaustive = DijkstraPlanner::new()
+        .with_early_exit(false)
+        .plan(&grid, source, target)
+        .unwrap();
+    for plan in [&exact, &eager, &exhaustive] {
+        assert_eq!(cost(&grid, source, plan), c);
+        assert_eq!(plan.nodes().last(), Some(&target));
+    }
+}
+
+#[test]
+fn test_quad_field() {
+    let grid: Grid = read_grid("./data/sample/test_nav1.map").unwrap();
+    let quad = QuadTree::from_grid(&grid, 4);
+    let target = ACoord { x: 1, y: 1 };
+    let field = DijkstraPlanner::new().field(&quad, target);
+    for &node in field.costs.keys() {
+        let path = field.descend(node).unwrap();
+        assert_eq!(path.last().copied().unwrap_or(node), field.target);
+    }
+}
@@ -0,0 +1,43 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::algo::best_first::BestFirstPlanner;
+use sabrina::algo::d_star::DStarPlanner;
+use sabrina::environment::hex::{HexGrid, offset_to_axial};
+use sabrina::global::types::{HEX_DIRECTIONS, Status};
+use sabrina::intelligence::sabrina::Sabrina;
+use sabrina::parser::hex::read_hex;
+use sabrina::sensor::lidar::Lidar;
+use std::fs;
+
+#[test]
+fn test_hex_display_round_trip() {
+    let path = "./data/sample/test_hex0.map";
+    let hex = read_hex(path).unwrap();
+    let rendered = format!("{hex}");
+    let expected = fs::read_to_string(path).unwrap();
+    assert_eq!(rendered, expected);
+}
+
+#[test]
+fn test_hex_navigation() {
+    let path = "./data/sample/test_hex0.map";
+    match read_hex(path) {
+        Ok(oracle) => {
+            let source = offset_to_axial(1, 1);
+            let target = offset_to_axial(12, 7);
+            let lidar = Lidar::with_beams(12, oracle.clone(), &HEX_DIRECTIONS);
+            let mut sabby = Sabrina::new(source, HexGrid::new(), lidar, AStarPlanner);
+            assert_eq!(Status::Complete, sabby.navigate(target));
+            assert_eq!(sabby.position, target);
+            let lidar = Lidar::with_beams(12, oracle.clone(), &HEX_DIRECTIONS);
+            let mut sabby = Sabrina::new(source, HexGrid::new(), lidar, BestFirstPlanner);
+            assert_eq!(Status::Complete, sabby.navigate(target));
+            let lidar = Lidar::with_beams(12, oracle.clone(), &HEX_DIRECTIONS);
+            let mut sabby = Sabrina::new(source, HexGrid::new(), lidar, DStarPlanner::new());
+            assert_eq!(Status::Complete, sabby.navigate(target));
+            assert_eq!(sabby.position, target);
+        }
+        Err(e) => {
+            panic!("Unexpected error in hex navigation\n{e:?}");
+        }
+    }
+}
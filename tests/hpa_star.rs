@@ -0,0 +1,185 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::algo::hpa_star::HpaStarPlanner;
+use sabrina::environment::quad::QuadTree;
+use sabrina::global::types::plan::Planner;
+use sabrina::global::types::{ACoord, Belief, Bounds, PlanIter, SpatialMap};
+use sabrina::parser::quad::read_quad;
+
+/// Cost of walking the plan, every step must move onto a neighbouring leaf
+fn walk<P: PlanIter>(quad: &QuadTree, source: ACoord, plan: &P) -> usize {
+    let mut cost = 0;
+    let mut prev = quad.encode(source);
+    for &coord in plan.iter() {
+        let next = quad.encode(coord);
+        assert!(
+            quad.neighbors(prev).contains(&next),
+            "{prev:?} -> {next:?} is not a step"
+        );
+        cost += quad.distance(prev, next);
+        prev = next;
+    }
+    cost
+}
+
+#[test]
+fn test_hpa_star() {
+    let quad = read_quad("./data/sample/test_nav0.map", 4).unwrap();
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+    let mut planner = HpaStarPlanner::new(2);
+    let plan = planner.plan(&quad, source, target).unwrap();
+    assert_eq!(
+        plan.iter().last().map(|&c| quad.encode(c)),
+        Some(quad.encode(target))
+    );
+    let optimal = AStarPlanner.plan(&quad, source, target).unwrap();
+    assert!(walk(&quad, source, &plan) >= walk(&quad, source, &optimal));
+    assert!(planner.entrances() > 0);
+}
+
+#[test]
+fn test_hpa_star_patch() {
+    let mut quad = read_quad("./data/sample/test_nav0.map", 4).unwrap();
+    let (source, target) = (ACoord { x: 15, y: 6 }, ACoord { x: 18, y: 1 });
+    let mut planner = HpaStarPlanner::new(2);
+    assert!(planner.plan(&quad, source, target).is_some());
+    // close the short way down, leaving the detour through the right room
+    let wall = Bounds {
+        min_x: 15,
+        min_y: 2,
+        max_x: 15,
+        max_y: 2,
+    };
+    quad.fill_rect(&wall, Belief::Occupied);
+    planner.invalidate(wall);
+    let patched = planner.plan(&quad, source, target).unwrap();
+    assert!(patched.iter().all(|&c| !quad.obstructed(c)));
+    let mut rebuilt = HpaStarPlanner::new(2);
+    let fresh = rebuilt.plan(&quad, source, target).unwrap();
+    assert_eq!(planner.entrances(), rebuilt.entrances());
+    assert_eq!(walk(&quad, source, &patched), walk(&quad, source, &fresh));
+    // closing the detour as well leaves no route
+    let seal = Bounds {
+        min_x: 18,
+        min_y: 2,
+        max_x: 18,
+        max_y: 2,
+    };
+    quad.fill_rect(&seal, Belief::Occupied);
+    planner.invalidate(seal);
+    assert!(planner.plan(&quad, source, target).is_none());
+}
+
+#[test]
+fn test_hpa_star_journal() {
+    let mut quad = read_quad("./data/sample/test_nav0.map", 4).unwrap();
+    let (source, target) = (ACoord { x: 15, y: 6 }, ACoord { x: 18, y: 1 });
+    let mut planner = HpaStarPlanner::new(2);
+    assert!(planner.plan(&quad, source, target).is_some());
+    // edits through the tree alone reach the abstract graph
+    quad.update_belief(&ACoord { x: 15, y: 2 }, Belief::Occupied);
+    let patched = planner.plan(&quad, source, target).unwrap();
+    assert!(patched.iter().all(|&c| !quad.obstructed(c)));
+    let mut rebuilt = HpaStarPlanner::new(2);
+    let fresh = rebuilt.plan(&quad, source, target).unwrap();
+    assert_eq!(planner.entrances(), rebuilt.entrances());
+    assert_eq!(walk(&quad, source, &patched), walk(&quad, source, &fresh));
+    quad.update_belief(&ACoord { x: 18, y: 2 }, Belief::Occupied);
+    assert!(planner.plan(&quad, source, target).is_none());
+    // a door in a wall seen whole at build time opens a new entrance
+    let mut quad = read_quad("./data/sample/test_nav0.map", 4).unwrap();
+    let mut planner = HpaStarPlanner::new(2);
+    let (source, target) = (ACoord { x: 9, y: 4 }, ACoord { x: 16, y: 4 });
+    let around = planner.plan(&quad, source, target).unwrap();
+    quad.update_belief(&ACoord { x: 14, y: 4 }, Belief::Free);
+    let through = planner.plan(&quad, source, target).unwrap();
+    let fresh = HpaStarPlanner::new(2).plan(&quad, source, target).unwrap();
+    assert_eq!(walk(&quad, source, &through), walk(&quad, source, &fresh));
+    assert!(walk(&quad, source, &through) < walk(&quad, source, &around));
+}
+
+#[test]
+fn test_hpa_star_other_tree() {
+    let mut quad = read_quad("./data/sample/test_nav0.map", 4).unwrap();
+    let (source, target) = (ACoord { x: 9, y: 4 }, ACoord { x: 16, y: 4 });
+    for _ in 0..5 {
+        quad.update_belief(&ACoord { x: 1, y: 1 }, Belief::Free);
+    }
+    let mut planner = HpaStarPlanner::new(2);
+    let around = planner.plan(&quad, source, target).unwrap();
+    // another tree with a door recorded early in a longer journal
+    let mut other = read_quad("./data/sample/test_nav0.map", 4).unwrap();
+    other.update_belief(&ACoord { x: 14, y: 4 }, Belief::Free);
+    for _ in 0..10 {
+        other.update_belief(&ACoord { x: 1, y: 1 }, Belief::Free);
+    }
+    assert!(other.generation() > quad.generation());
+    assert_ne!(other.id(), quad.id());
+    assert_ne!(quad.clone().id(), quad.id());
+    let through = planner.plan(&other, source, target).unwrap();
+    let fresh = HpaStarPlanner::new(2).plan(&other, source, target).unwrap();
+    assert_eq!(walk(&other, source, &through), walk(&other, source, &fresh));
+    assert!(walk(&other, source, &through) < walk(&quad, source, &around));
+}
+
+#[test]
+fn test_hpa_star_journal_bounded() {
+    let mut quad = read_quad("./data/sample/test_nav0.map", 4).unwrap();
+    let (source, target) = (ACoord { x: 9, y: 4 }, ACoord { x: 16, y: 4 });
+    let mut planner = HpaStarPlanner::new(2);
+    planner.plan(&quad, source, target).unwrap();
+    let seen = quad.generation();
+    // a robot scanning for long keeps recording the same free cell
+    for _ in 0..17_000 {
+        quad.update_belief(&ACoord { x: 1, y: 1 }, Belief::Free);
+    }
+    assert!(quad.generation() >= seen + 17_000);
+    assert_eq!(quad.journal_since(quad.generation() - 10).unwrap().len(), 10);
+    assert!(quad.journal_since(seen).is_none());
+    // entries dropped before the graph saw them make it rebuild
+    quad.update_belief(&ACoord { x: 14, y: 4 }, Belief::Free);
+    let through = planner.plan(&quad, source, target).unwrap();
+    let fresh = HpaStarPlanner::new(2).plan(&quad, source, target).unwrap();
+    assert_eq!(walk(&quad, source, &through), walk(&quad, source, &fresh));
+}
+
+#[test]
+fn test_hpa_star_large() {
+    // routes across a 4096 square map search a fraction of what flat A* does
+    let side = 4096;
+    let bounds = Bounds {
+        min_x: 0,
+        min_y: 0,
+        max_x: side - 1,
+        max_y: side - 1,
+    };
+    // walls every 128 cells with a single door alternating top and bottom
+    let quad = QuadTree::from_fn(13, &bounds, |c| {
+        let wall = c.x % 128 == 127;
+        let door = if (c.x / 128) % 2 == 0 {
+            c.y < 4
+        } else {
+            c.y >= side - 4
+        };
+        if wall && !door {
+            Belief::Occupied
+        } else {
+            Belief::Free
+        }
+    });
+    let (source, target) = (
+        ACoord { x: 2, y: 2000 },
+        ACoord {
+            x: side - 3,
+            y: 2000,
+        },
+    );
+    let mut planner = HpaStarPlanner::new(5);
+    assert!(planner.plan(&quad, source, target).is_some());
+    let plan = planner.plan(&quad, source, target).unwrap();
+    let flat = AStarPlanner.plan(&quad, source, target).unwrap();
+    assert!(walk(&quad, source, &plan) >= walk(&quad, source, &flat));
+    // the kept abstract graph only leaves the corridors along the route to refine
+    let (hpa, astar) = (plan.stats, flat.stats);
+    assert!(hpa.expanded * 3 < astar.expanded, "{hpa:?} {astar:?}");
+    assert!(hpa.pushes * 2 < astar.pushes, "{hpa:?} {astar:?}");
+}
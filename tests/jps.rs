@@ -0,0 +1,94 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::algo::dijkstra::DijkstraPlanner;
+use sabrina::algo::jps::JpsPlanner;
+use sabrina::environment::grid::Grid;
+use sabrina::global::types::plan::Planner;
+use sabrina::global::types::{ACoord, Belief, Bounds, Connectivity, PlanIter, SpatialMap};
+use sabrina::parser::grid::read_grid;
+
+/// Cost of the plan, every step must move onto a neighbouring cell
+fn walk<P: PlanIter>(grid: &Grid, source: ACoord, plan: &P) -> usize {
+    let mut prev = source;
+    let mut cost = 0;
+    for &next in plan.iter() {
+        assert!(grid.neighbors(prev).contains(&next), "{prev:?} -> {next:?}");
+        cost += grid.distance(prev, next);
+        prev = next;
+    }
+    cost
+}
+
+#[test]
+fn test_jps() {
+    let connections = [Connectivity::Four, Connectivity::Octile, Connectivity::Euclidean];
+    for name in ["test_nav0", "test_nav1", "test_quad0", "test_quad1"] {
+        for connectivity in connections {
+            let grid = read_grid(&format!("./data/sample/{name}.map"))
+                .unwrap()
+                .with_connectivity(connectivity);
+            let free: Vec<ACoord> = grid
+                .information
+                .iter()
+                .filter(|(_, b)| **b != Belief::Occupied)
+                .map(|(&c, _)| c)
+                .collect();
+            for &target in free.iter().step_by(3) {
+                let field = DijkstraPlanner::new().field(&grid, target);
+                for &source in free.iter().step_by(2) {
+                    let plan = JpsPlanner.plan(&grid, source, target);
+                    match field.cost(source) {
+                        Some(cost) => {
+                            let plan = plan.unwrap();
+                            let walked = walk(&grid, source, &plan);
+                            assert_eq!(walked, cost, "{name} {source:?} {target:?}");
+                            if source != target {
+                                assert_eq!(plan.nodes().last(), Some(&target));
+                            }
+                        }
+                        None => assert!(plan.is_none(), "{name} {source:?} {target:?}"),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_jps_warehouse() {
+    // aisles of shelving with cross aisles every so often
+    let mut grid = Grid::new();
+    grid.fill_region(
+        &Bounds {
+            min_x: 0,
+            min_y: 0,
+            max_x: 199,
+            max_y: 199,
+        },
+        Belief::Free,
+    );
+    for x in (4..196).step_by(4) {
+        for y in 2..198 {
+            if y % 40 != 0 {
+                grid.information.insert(ACoord { x, y }, Belief::Occupied);
+            }
+        }
+    }
+    let (source, target) = (ACoord { x: 1, y: 3 }, ACoord { x: 198, y: 190 });
+    for connectivity in [Connectivity::Four, Connectivity::Octile] {
+        let grid = grid.clone().with_connectivity(connectivity);
+        let jps = JpsPlanner.plan(&grid, source, target).unwrap();
+        let astar = AStarPlanner.plan(&grid, source, target).unwrap();
+        assert_eq!(walk(&grid, source, &jps), walk(&grid, source, &astar));
+        // jumping along the aisles expands only the jump points
+        let (jumped, expanded) = (jps.stats.expanded, astar.stats.expanded);
+        assert!(jumped * 10 < expanded, "{connectivity:?} {jumped} against {expanded}");
+    }
+    // the free space around the bounds is used to go round a wall
+    let mut walled = Grid::new();
+    for y in 0..10 {
+        walled.information.insert(ACoord { x: 5, y }, Belief::Occupied);
+        walled.update_bounds(ACoord { x: 5, y });
+    }
+    let plan = JpsPlanner.plan(&walled, ACoord { x: 4, y: 5 }, ACoord { x: 6, y: 5 }).unwrap();
+    assert!(plan.iter().any(|c| c.y == 10 || c.y == -1));
+}
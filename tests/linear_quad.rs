@@ -0,0 +1,96 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::environment::linear_quad::LinearQuadTree;
+use sabrina::environment::quad::QuadTree;
+use sabrina::global::types::plan::Planner;
+use sabrina::global::types::{ACoord, Belief, Bounds, Connectivity, HCoord, PlanIter, SpatialMap};
+use sabrina::hierarchy::morton::{demorton, morton, step};
+use sabrina::parser::quad::read_quad;
+
+fn sorted(mut nodes: Vec<HCoord>) -> Vec<HCoord> {
+    nodes.sort_by_key(|n| (n.l, n.x, n.y));
+    nodes
+}
+
+fn assert_equivalent(quad: &QuadTree, linear: &LinearQuadTree) {
+    let mut expected: Vec<_> = quad.leaves().map(|l| (l.node, l.belief)).collect();
+    let mut found: Vec<_> = linear.leaves().map(|l| (l.node, l.belief)).collect();
+    expected.sort_by_key(|(n, _)| (n.l, n.x, n.y));
+    found.sort_by_key(|(n, _)| (n.l, n.x, n.y));
+    assert_eq!(expected, found);
+    for x in -10..20 {
+        for y in -10..20 {
+            let coord = ACoord { x, y };
+            let node = quad.encode(coord);
+            assert_eq!(node, linear.encode(coord));
+            assert_eq!(quad.obstructed(coord), linear.obstructed(coord));
+            assert_eq!(quad.belief(node), linear.belief(node));
+            assert_eq!(sorted(quad.neighbors(node)), sorted(linear.neighbors(node)));
+        }
+    }
+}
+
+#[test]
+fn test_morton_codes() {
+    for (x, y) in [(0, 0), (5, 3), (-1, 0), (-7, -12), (1 << 20, -(1 << 19))] {
+        let coord = ACoord { x, y };
+        assert_eq!(demorton(morton(coord)), coord);
+        for (dx, dy) in [(1, 0), (-3, 2), (0, -8), (16, 16)] {
+            let moved = ACoord {
+                x: x + dx,
+                y: y + dy,
+            };
+            assert_eq!(step(morton(coord), dx, dy), morton(moved));
+        }
+    }
+}
+
+#[test]
+fn test_linear_equivalence() {
+    for connectivity in [Connectivity::Four, Connectivity::Octile] {
+        let mut quad = QuadTree::init(4).with_connectivity(connectivity);
+        let mut linear = LinearQuadTree::init(4).with_connectivity(connectivity);
+        assert_equivalent(&quad, &linear);
+        for (x, y, belief) in [
+            (3, 3, Belief::Occupied),
+            (4, 3, Belief::Free),
+            (9, 2, Belief::Occupied),
+            (-2, 5, Belief::Free),
+            (3, 3, Belief::Free),
+        ] {
+            quad.update_belief(&ACoord { x, y }, belief);
+            linear.update_belief(&ACoord { x, y }, belief);
+        }
+        assert_equivalent(&quad, &linear);
+        for (pos, hit) in [((0, 0), (6, 6)), ((1, 1), (-4, 1)), ((2, 8), (2, -3))] {
+            let (pos, hit) = (ACoord { x: pos.0, y: pos.1 }, ACoord { x: hit.0, y: hit.1 });
+            quad.insert_ray(pos, hit);
+            linear.insert_ray(pos, hit);
+        }
+        assert_equivalent(&quad, &linear);
+        for belief in [Belief::Free, Belief::Occupied] {
+            let rect = Bounds {
+                min_x: -3,
+                min_y: 1,
+                max_x: 12,
+                max_y: 4,
+            };
+            quad.fill_rect(&rect, belief);
+            linear.fill_rect(&rect, belief);
+            assert_equivalent(&quad, &linear);
+        }
+        quad.initialize(ACoord { x: 0, y: 0 }, ACoord { x: -9, y: 2 });
+        linear.initialize(ACoord { x: 0, y: 0 }, ACoord { x: -9, y: 2 });
+        assert_equivalent(&quad, &linear);
+    }
+}
+
+#[test]
+fn test_linear_planning() {
+    let quad = read_quad("./data/sample/test_nav0.map", 4).unwrap();
+    let linear = LinearQuadTree::from_quad(&quad);
+    assert_equivalent(&quad, &linear);
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+    let expected = AStarPlanner.plan(&quad, source, target).unwrap();
+    let found = AStarPlanner.plan(&linear, source, target).unwrap();
+    assert_eq!(expected.nodes().len(), found.nodes().len());
+}
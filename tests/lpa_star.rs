@@ -0,0 +1,110 @@
+use sabrina::algo::d_star::DStarPlanner;
+use sabrina::algo::lpa_star::LpaStarPlanner;
+use sabrina::environment::quad::QuadTree;
+use sabrina::global::types::plan::{CellChange, Planner, Provenance};
+use sabrina::global::types::{ACoord, Belief, Bounds, Connectivity, SpatialMap};
+
+mod common;
+use common::{arena, interior, next, optimal, walk};
+
+const SIDE: isize = 14;
+
+/// Flips a few interior cells other than the source and target
+fn flip<S: SpatialMap>(env: &mut S, state: &mut u64, keep: [ACoord; 2]) -> Vec<CellChange> {
+    let mut changes = Vec::new();
+    for _ in 0..1 + next(state) % 4 {
+        let coord = interior(state, SIDE);
+        if keep.contains(&coord) {
+            continue;
+        }
+        let old = env.belief(env.encode(coord));
+        let new = match old {
+            Belief::Occupied => Belief::Free,
+            _ => Belief::Occupied,
+        };
+        let cell = Bounds {
+            min_x: coord.x,
+            min_y: coord.y,
+            max_x: coord.x,
+            max_y: coord.y,
+        };
+        env.fill_region(&cell, new);
+        changes.push(CellChange { coord, old, new });
+    }
+    changes
+}
+
+#[test]
+fn test_updates_match_fresh_search() {
+    for connectivity in [Connectivity::Four, Connectivity::Octile] {
+        for seed in 0..25 {
+            let mut state = seed;
+            let mut grid = arena(&mut state, SIDE, connectivity, (1, 4));
+            let (source, target) = (interior(&mut state, SIDE), interior(&mut state, SIDE));
+            grid.information.insert(source, Belief::Free);
+            grid.information.insert(target, Belief::Free);
+            let mut lpa = LpaStarPlanner::new();
+            let mut dstar = DStarPlanner::new();
+            for round in 0..30 {
+                let expected = optimal(&grid, source, target);
+                let lpa_cost = lpa.plan(&grid, source, target).map(|p| walk(&grid, source, &p));
+                let dstar_cost = dstar.plan(&grid, source, target).map(|p| walk(&grid, source, &p));
+                assert_eq!(lpa_cost, expected, "{connectivity:?} seed {seed} round {round}");
+                assert_eq!(lpa_cost, dstar_cost, "{connectivity:?} seed {seed} round {round}");
+                let changes = flip(&mut grid, &mut state, [source, target]);
+                lpa.update(&grid, &changes);
+                dstar.update(&grid, &changes);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_quad_updates() {
+    for seed in 0..10 {
+        let mut state = seed;
+        let grid = arena(&mut state, SIDE, Connectivity::Octile, (1, 4));
+        let mut quad = QuadTree::from_grid(&grid, 4);
+        let (source, target) = (interior(&mut state, SIDE), interior(&mut state, SIDE));
+        for coord in [source, target] {
+            let cell = Bounds {
+                min_x: coord.x,
+                min_y: coord.y,
+                max_x: coord.x,
+                max_y: coord.y,
+            };
+            quad.fill_region(&cell, Belief::Free);
+        }
+        let mut lpa = LpaStarPlanner::new();
+        let mut nodes = None;
+        for round in 0..20 {
+            let expected = optimal(&quad, source, target);
+            let plan = lpa.plan(&quad, source, target);
+            let found = plan.as_ref().map(|p| walk(&quad, source, p));
+            assert_eq!(found, expected, "seed {seed} round {round}");
+            // nodes merged or split around the search are patched rather than searched afresh
+            let kept = nodes == Some((quad.encode(source), quad.encode(target)));
+            if let Some(p) = plan.filter(|_| kept) {
+                assert_eq!(p.stats.provenance, Provenance::Repaired, "seed {seed} round {round}");
+            }
+            nodes = Some((quad.encode(source), quad.encode(target)));
+            let changes = flip(&mut quad, &mut state, [source, target]);
+            lpa.update(&quad, &changes);
+        }
+    }
+}
+
+#[test]
+fn test_new_query_starts_over() {
+    let mut state = 7;
+    let mut grid = arena(&mut state, SIDE, Connectivity::Octile, (1, 4));
+    let mut lpa = LpaStarPlanner::new();
+    let (a, b, c) = (ACoord { x: 1, y: 1 }, ACoord { x: 12, y: 12 }, ACoord { x: 12, y: 1 });
+    for coord in [a, b, c] {
+        grid.information.insert(coord, Belief::Free);
+    }
+    for (source, target) in [(a, b), (a, c), (c, b), (a, b)] {
+        let found = lpa.plan(&grid, source, target).map(|p| walk(&grid, source, &p));
+        assert_eq!(found, optimal(&grid, source, target), "{source:?} {target:?}");
+    }
+}
@@ -0,0 +1,85 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::algo::best_first::BestFirstPlanner;
+use sabrina::environment::octree::Octree;
+use sabrina::global::types::plan::VolumePlanner;
+use sabrina::global::types::{Belief, VCoord, VOXEL_ROSE, VolumetricMap};
+use sabrina::parser::layered::read_layers;
+use sabrina::sensor::lidar3::Lidar3;
+
+#[test]
+fn test_octree_compression() {
+    let mut octree = Octree::init(3);
+    for x in 0..4 {
+        for y in 0..4 {
+            for z in 0..4 {
+                octree.update_belief(VCoord { x, y, z }, Belief::Free);
+            }
+        }
+    }
+    assert_eq!(octree.information.len(), 1);
+    octree.update_belief(VCoord { x: 1, y: 2, z: 3 }, Belief::Occupied);
+    assert_eq!(octree.information.len(), 15);
+    assert!(octree.obstructed(VCoord { x: 1, y: 2, z: 3 }));
+    octree.update_belief(VCoord { x: 1, y: 2, z: 3 }, Belief::Free);
+    assert_eq!(octree.information.len(), 1);
+}
+
+#[test]
+fn test_face_neighbors() {
+    let octree = read_layers("./data/sample/test_layers0.map", 4).unwrap();
+    for (&node, &belief) in &octree.information {
+        if belief == Belief::Occupied {
+            continue;
+        }
+        for neigh in octree.neighbors(node) {
+            assert_ne!(octree.belief(neigh), Belief::Occupied);
+            assert!(
+                octree.neighbors(neigh).contains(&node),
+                "{node:?} -> {neigh:?} must be symmetric"
+            );
+        }
+    }
+}
+
+#[test]
+fn test_layered_planning() {
+    match read_layers("./data/sample/test_layers0.map", 4) {
+        Ok(oracle) => {
+            assert!(oracle.obstructed(VCoord { x: 3, y: 2, z: 0 }));
+            assert!(!oracle.obstructed(VCoord { x: 3, y: 2, z: 1 }));
+            assert!(oracle.obstructed(VCoord { x: 3, y: 2, z: 2 }));
+            let source = VCoord { x: 1, y: 1, z: 0 };
+            let target = VCoord { x: 5, y: 1, z: 0 };
+            let astar = AStarPlanner.plan_volume(&oracle, source, target).unwrap();
+            let best = BestFirstPlanner.plan_volume(&oracle, source, target).unwrap();
+            for plan in [astar, best] {
+                // the wall on the ground is only passable through the layer above
+                assert!(plan.iter().any(|v| v.z == 1));
+                assert_eq!(plan.iter().last(), Some(&target));
+                // walked forward from the source, one voxel at a time
+                let mut prev = source;
+                for (&voxel, &step) in plan.iter().zip(&plan.steps) {
+                    assert_eq!(step, oracle.distance(oracle.encode(prev), oracle.encode(voxel)));
+                    prev = voxel;
+                }
+                assert_eq!(plan.cost, plan.steps.iter().sum::<usize>());
+                assert!(plan.stats.expanded > 0);
+            }
+        }
+        Err(e) => {
+            panic!("Unexpected error in layered planning\n{e:?}");
+        }
+    }
+}
+
+#[test]
+fn test_lidar3() {
+    let oracle = read_layers("./data/sample/test_layers0.map", 4).unwrap();
+    let lidar = Lidar3::new(10, oracle);
+    let measure = lidar.measure(VCoord { x: 1, y: 1, z: 0 });
+    assert!(measure.data.contains(&Some(VCoord { x: 2, y: 0, z: 0 })));
+    assert!(measure.data.contains(&Some(VCoord { x: 0, y: 0, z: 2 })));
+    // unknown space below the ground does not return
+    let down = VOXEL_ROSE.iter().position(|&d| d == VCoord { x: 0, y: 0, z: -1 });
+    assert_eq!(measure.data[down.unwrap()], None);
+}
@@ -0,0 +1,156 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::algo::best_first::BestFirstPlanner;
+use sabrina::algo::bidirectional_a_star::BidirectionalAStarPlanner;
+use sabrina::algo::d_star::DStarPlanner;
+use sabrina::algo::dijkstra::DijkstraPlanner;
+use sabrina::algo::lpa_star::LpaStarPlanner;
+use sabrina::algo::theta_star::ThetaStarPlanner;
+use sabrina::environment::grid::Grid;
+use sabrina::global::types::plan::{CellChange, Plan, Planner, Provenance};
+use sabrina::global::types::{ACoord, Belief, Connectivity, PlanIter, SpatialMap};
+use sabrina::parser::grid::read_grid;
+
+mod common;
+use common::{arena, interior, optimal, walk};
+
+const SIDE: isize = 14;
+
+/// Checks the plan walks forward from the source and its costs add up
+fn checked(grid: &Grid, source: ACoord, target: ACoord, plan: &Plan) -> usize {
+    assert_eq!(plan.nodes().last().copied().unwrap_or(source), target);
+    assert_eq!(plan.steps.len(), plan.nodes().len());
+    let mut prev = source;
+    for (&coord, &step) in plan.iter().zip(&plan.steps) {
+        assert_eq!(step, grid.distance(prev, coord));
+        prev = coord;
+    }
+    assert_eq!(plan.cost, plan.steps.iter().sum::<usize>());
+    assert_eq!(walk(grid, source, plan), plan.cost);
+    plan.cost
+}
+
+#[test]
+fn test_forward_order_and_cost() {
+    let mut state = 5;
+    for connectivity in [Connectivity::Four, Connectivity::Octile] {
+        for _ in 0..30 {
+            let grid = arena(&mut state, SIDE, connectivity, (1, 4));
+            let (source, target) = (interior(&mut state, SIDE), interior(&mut state, SIDE));
+            if grid.obstructed(source) {
+                continue;
+            }
+            let expected = optimal(&grid, source, target);
+            let mut planners: Vec<Box<dyn Planner<Grid>>> = vec![
+                Box::new(AStarPlanner),
+                Box::new(DStarPlanner::new()),
+                Box::new(DijkstraPlanner::new()),
+                Box::new(BidirectionalAStarPlanner),
+                Box::new(LpaStarPlanner::new()),
+            ];
+            for planner in planners.iter_mut() {
+                let plan = planner.plan(&grid, source, target);
+                if grid.obstructed(target) {
+                    assert!(plan.is_none());
+                    continue;
+                }
+                assert_eq!(plan.map(|p| checked(&grid, source, target, &p)), expected);
+            }
+            // greedy plans carry their true cost, never below the optimum
+            if let Some(plan) = BestFirstPlanner.plan(&grid, source, target) {
+                assert!(checked(&grid, source, target, &plan) >= expected.unwrap());
+            }
+        }
+    }
+}
+
+#[test]
+fn test_search_stats() {
+    let mut state = 11;
+    for _ in 0..20 {
+        let grid = arena(&mut state, SIDE, Connectivity::Octile, (1, 4));
+        let (source, target) = (interior(&mut state, SIDE), interior(&mut state, SIDE));
+        let Some(plan) = AStarPlanner.plan(&grid, source, target) else {
+            continue;
+        };
+        let stats = plan.stats;
+        assert_eq!(stats.provenance, Provenance::Computed);
+        assert!(stats.pushes >= stats.expanded);
+        assert!(source == target || stats.expanded > 0);
+        assert_eq!(plan.bound, None);
+    }
+}
+
+#[test]
+fn test_repaired_plans() {
+    let mut state = 23;
+    for _ in 0..20 {
+        let mut grid = arena(&mut state, SIDE, Connectivity::Octile, (1, 4));
+        let (source, target) = (interior(&mut state, SIDE), interior(&mut state, SIDE));
+        let mut d_star = DStarPlanner::new();
+        let mut lpa_star = LpaStarPlanner::new();
+        let planners: [&mut dyn Planner<Grid>; 2] = [&mut d_star, &mut lpa_star];
+        for planner in planners {
+            let Some(plan) = planner.plan(&grid, source, target) else {
+                continue;
+            };
+            assert_eq!(plan.stats.provenance, Provenance::Computed);
+            assert!(plan.stats.expanded > 0 || source == target);
+            let again = planner.plan(&grid, source, target).unwrap();
+            assert_eq!(again.stats.provenance, Provenance::Repaired);
+            // nothing changed, the kept search already holds the plan
+            assert_eq!(again.stats.expanded, 0);
+            assert_eq!(again.cost, plan.cost);
+        }
+        // blocking a cell of the plan makes the repair do real work
+        let mut d_star = DStarPlanner::new();
+        let Some(plan) = d_star.plan(&grid, source, target) else {
+            continue;
+        };
+        let Some(&coord) = plan.nodes().iter().rev().nth(1) else {
+            continue;
+        };
+        grid.information.insert(coord, Belief::Occupied);
+        let change = CellChange {
+            coord,
+            old: Belief::Free,
+            new: Belief::Occupied,
+        };
+        d_star.update(&grid, &[change]);
+        let repaired = d_star.plan(&grid, source, target);
+        assert_eq!(repaired.as_ref().map(|p| p.cost), optimal(&grid, source, target));
+        if let Some(repaired) = repaired {
+            assert_eq!(repaired.stats.provenance, Provenance::Repaired);
+            assert!(repaired.stats.expanded > 0);
+        }
+    }
+}
+
+#[test]
+fn test_bidirectional_expands_less() {
+    let grid = read_grid("./data/sample/test_nav0.map").unwrap();
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+    let one = AStarPlanner.plan(&grid, source, target).unwrap();
+    let both = BidirectionalAStarPlanner.plan(&grid, source, target).unwrap();
+    assert_eq!(one.cost, both.cost);
+    assert!(both.stats.expanded < one.stats.expanded);
+}
+
+#[test]
+fn test_segment_costs_share_units() {
+    let mut state = 17;
+    for connectivity in [Connectivity::Four, Connectivity::Octile] {
+        for _ in 0..20 {
+            let grid = arena(&mut state, SIDE, connectivity, (1, 4));
+            let (source, target) = (interior(&mut state, SIDE), interior(&mut state, SIDE));
+            let Some(cells) = AStarPlanner.plan(&grid, source, target) else {
+                continue;
+            };
+            // straight segments never cost more than walking the cells, each rounded up once
+            let plan = ThetaStarPlanner::new().plan(&grid, source, target).unwrap();
+            let rounded = cells.cost + plan.steps.len();
+            assert!(plan.cost <= rounded, "{connectivity:?} {} > {}", plan.cost, cells.cost);
+            assert!(plan.cost >= grid.segment(source, target));
+            assert_eq!(plan.cost, plan.steps.iter().sum::<usize>());
+        }
+    }
+}
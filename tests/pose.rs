@@ -0,0 +1,132 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::environment::grid::Grid;
+use sabrina::global::types::plan::Planner;
+use sabrina::global::types::{ACoord, Belief, COMPASS_ROSE, Frame, HCoord, PlanIter, Pose, Status};
+use sabrina::hierarchy::encoding::decode_hier;
+use sabrina::intelligence::sabrina::Sabrina;
+use sabrina::parser::grid::read_grid;
+use sabrina::sensor::lidar::Lidar;
+use std::f64::consts::{FRAC_PI_2, FRAC_PI_4};
+
+const EPSILON: f64 = 1e-9;
+
+fn close(a: &Pose, b: &Pose) -> bool {
+    (a.x - b.x).abs() < EPSILON
+        && (a.y - b.y).abs() < EPSILON
+        && (a.heading - b.heading).abs() < EPSILON
+}
+
+#[test]
+fn test_frame_transforms() {
+    let frame = Frame::new(0.5, Pose::new(2.0, -1.0, FRAC_PI_2));
+    let pose = Pose::new(1.3, 0.4, 0.25);
+    assert!(close(&frame.to_world(&frame.to_map(&pose)), &pose));
+    // map x runs along world y once rotated a quarter turn
+    let centre = frame.centre(ACoord { x: 0, y: 0 });
+    assert!(close(&centre, &Pose::new(1.75, -0.75, FRAC_PI_2)));
+    assert_eq!(frame.cell(&centre), ACoord { x: 0, y: 0 });
+    assert_eq!(frame.cell(&Pose::new(2.1, -0.9, 0.0)), ACoord { x: 0, y: -1 });
+    let unit = Frame::default();
+    assert_eq!(unit.cell(&Pose::new(-0.2, 3.9, 0.0)), ACoord { x: -1, y: 3 });
+}
+
+#[test]
+fn test_decode_hier() {
+    assert_eq!(decode_hier(HCoord { l: 0, x: 3, y: 5 }), (3.5, 5.5));
+    assert_eq!(decode_hier(HCoord { l: 2, x: 4, y: 8 }), (6.0, 10.0));
+}
+
+#[test]
+fn test_pose_navigation() {
+    let path = "./data/sample/test_nav0.map";
+    match read_grid(path) {
+        Ok(oracle) => {
+            let frame = Frame::new(0.25, Pose::new(10.0, 5.0, 0.0));
+            // neither pose lies on a cell centre
+            let source = Pose::new(10.3, 5.33, 0.0);
+            let target = Pose::new(14.61, 5.8, 1.0);
+            let mut planner = AStarPlanner;
+            let plan = planner.plan_pose(&oracle, &frame, &source, &target).unwrap();
+            let cells = planner.plan(&oracle, ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+            assert_eq!(plan.nodes(), cells.unwrap().nodes());
+
+            let lidar = Lidar::new(100, oracle.clone());
+            let mut sabby = Sabrina::with_frame(source, frame, Grid::new(), lidar, AStarPlanner);
+            assert_eq!(sabby.position, ACoord { x: 1, y: 1 });
+            assert_eq!(Status::Complete, sabby.navigate_pose(&target));
+            assert_eq!(sabby.position, ACoord { x: 18, y: 3 });
+            assert!(close(&sabby.pose, &target));
+        }
+        Err(e) => {
+            panic!("Unexpected error in pose navigation\n{e:?}");
+        }
+    }
+}
+
+#[test]
+fn test_measure_turned_pose() {
+    let mut oracle = Grid::new();
+    oracle.information.insert(ACoord { x: 5, y: 2 }, Belief::Occupied);
+    oracle.information.insert(ACoord { x: 2, y: 6 }, Belief::Occupied);
+    oracle.information.insert(ACoord { x: 6, y: 4 }, Belief::Occupied);
+    let lidar = Lidar::new(10, oracle);
+    let frame = Frame::default();
+    // the first beam looks ahead, the third to the left
+    let ahead = lidar.measure_pose(&Pose::new(2.3, 2.7, 0.0), &frame);
+    assert_eq!(ahead.data[0], Some(ACoord { x: 3, y: 0 }));
+    assert_eq!(ahead.data[2], Some(ACoord { x: 0, y: 4 }));
+    let turned = lidar.measure_pose(&Pose::new(2.3, 2.7, FRAC_PI_2), &frame);
+    assert_eq!(turned.data[0], Some(ACoord { x: 0, y: 4 }));
+    assert_eq!(turned.data[6], Some(ACoord { x: 3, y: 0 }));
+    // off the lattice the beam crosses (4, 3), (5, 3) and (5, 4) on its way
+    let slanted = lidar.measure_pose(&Pose::new(2.5, 2.5, 0.5f64.atan()), &frame);
+    assert_eq!(slanted.data[0], Some(ACoord { x: 4, y: 2 }));
+    // the lattice beams of with_beams keep their directions
+    let mut oracle = Grid::new();
+    oracle.information.insert(ACoord { x: 5, y: 2 }, Belief::Occupied);
+    let lattice = Lidar::with_beams(10, oracle, &COMPASS_ROSE);
+    let fixed = lattice.measure_pose(&Pose::new(2.3, 2.7, FRAC_PI_4), &frame);
+    assert_eq!(fixed.data[0], Some(ACoord { x: 3, y: 0 }));
+}
+
+#[test]
+fn test_control_moves_continuously() {
+    let mut oracle = Grid::new();
+    oracle.information.insert(ACoord { x: 2, y: 2 }, Belief::Occupied);
+    let lidar = Lidar::new(10, oracle.clone());
+    let frame = Frame::default();
+    let source = Pose::new(1.2, 1.7, 0.0);
+    let mut sabby = Sabrina::with_frame(source, frame, Grid::new(), lidar, AStarPlanner);
+    assert_eq!(sabby.control(ACoord { x: 2, y: 1 }), Status::Enroute);
+    assert_eq!(sabby.position, ACoord { x: 2, y: 1 });
+    assert!(close(&sabby.pose, &Pose::new(2.5, 1.5, (-0.2f64).atan2(1.3))));
+    // the wall lies halfway along the segment, the pose settles in the cell before it
+    let source = Pose::new(1.5, 1.5, 0.0);
+    let lidar = Lidar::new(10, oracle.clone());
+    let mut sabby = Sabrina::with_frame(source, frame, oracle, lidar, AStarPlanner);
+    assert_eq!(sabby.control(ACoord { x: 3, y: 2 }), Status::Blocked);
+    assert_eq!(sabby.position, ACoord { x: 2, y: 1 });
+    assert!(close(&sabby.pose, &Pose::new(2.5, 1.5, 0.5f64.atan())));
+    assert_eq!(frame.cell(&sabby.pose), sabby.position);
+}
+
+#[test]
+fn test_scan_records_crossed_cells() {
+    let mut oracle = Grid::new();
+    oracle.information.insert(ACoord { x: 6, y: 4 }, Belief::Occupied);
+    let lidar = Lidar::new(10, oracle.clone());
+    let frame = Frame::default();
+    let pose = Pose::new(2.5, 2.5, 0.5f64.atan());
+    let measure = lidar.measure_pose(&pose, &frame);
+    let crossed = [(1, 0), (1, 1), (2, 1), (3, 1), (3, 2)].map(|(x, y)| ACoord { x, y });
+    assert_eq!(measure.crossed[0], crossed);
+    // the map holds what the beam crossed, (3, 3) lies off the line drawn to the hit
+    let lidar = Lidar::new(10, oracle);
+    let mut sabby = Sabrina::with_frame(pose, frame, Grid::new(), lidar, AStarPlanner);
+    assert_eq!(sabby.navigate(ACoord { x: 2, y: 2 }), Status::Complete);
+    for cell in crossed {
+        let cell = ACoord { x: cell.x + 2, y: cell.y + 2 };
+        assert_eq!(sabby.environment.information.get(&cell), Some(&Belief::Free), "{cell:?}");
+    }
+    assert_eq!(sabby.environment.information.get(&ACoord { x: 6, y: 4 }), Some(&Belief::Occupied));
+}
@@ -0,0 +1,93 @@
+use sabrina::algo::rrt_star::RrtStarPlanner;
+use sabrina::environment::grid::Grid;
+use sabrina::global::rng::Rng;
+use sabrina::global::types::plan::Planner;
+use sabrina::global::types::{ACoord, Belief, Bounds, Opacity, PlanIter, SpatialMap, Status};
+use sabrina::intelligence::sabrina::Sabrina;
+use sabrina::parser::grid::read_grid;
+use sabrina::sensor::lidar::Lidar;
+
+/// Straight length of the waypoints, every segment clear of occupied cells
+fn length<S: SpatialMap, P: PlanIter>(env: &S, source: ACoord, plan: &P) -> f64 {
+    let mut prev = source;
+    let mut total = 0.0;
+    for &coord in plan.iter() {
+        assert!(env.line_of_sight(prev, coord, Opacity::Transparent), "{prev:?} {coord:?}");
+        total += ((coord.x - prev.x) as f64).hypot((coord.y - prev.y) as f64);
+        prev = coord;
+    }
+    total
+}
+
+#[test]
+fn test_rng() {
+    let (mut a, mut b) = (Rng::new(7), Rng::new(7));
+    let draws: Vec<u64> = (0..100).map(|_| a.next_u64()).collect();
+    assert!(draws.iter().all(|&d| d == b.next_u64()));
+    assert_ne!(draws[0], Rng::new(8).next_u64());
+    let mut rng = Rng::new(1);
+    let samples: Vec<f64> = (0..10_000).map(|_| rng.range(-2.0, 3.0)).collect();
+    assert!(samples.iter().all(|&s| (-2.0..3.0).contains(&s)));
+    let mean = samples.iter().sum::<f64>() / samples.len() as f64;
+    assert!((mean - 0.5).abs() < 0.1, "{mean}");
+}
+
+#[test]
+fn test_plans_are_clear_and_reproducible() {
+    let grid = read_grid("./data/sample/test_nav0.map").unwrap();
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+    for seed in 0..10 {
+        for mut planner in [RrtStarPlanner::new(), RrtStarPlanner::rrt()] {
+            planner = planner.with_seed(seed);
+            let plan = planner.plan(&grid, source, target).unwrap();
+            assert_eq!(plan.nodes().last(), Some(&target));
+            length(&grid, source, &plan);
+            let again = planner.plan(&grid, source, target).unwrap();
+            assert_eq!(plan.nodes(), again.nodes());
+        }
+    }
+}
+
+#[test]
+fn test_rewiring_shortens_plans() {
+    let grid = read_grid("./data/sample/test_nav0.map").unwrap();
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+    let (mut rrt, mut rrt_star) = (0.0, 0.0);
+    for seed in 0..10 {
+        let plan = RrtStarPlanner::rrt().with_seed(seed).plan(&grid, source, target).unwrap();
+        rrt += length(&grid, source, &plan);
+        let plan = RrtStarPlanner::new().with_seed(seed).plan(&grid, source, target).unwrap();
+        rrt_star += length(&grid, source, &plan);
+    }
+    assert!(rrt_star <= rrt, "{rrt_star} {rrt}");
+}
+
+#[test]
+fn test_unreachable() {
+    // a free cell inside a ring of walls
+    let mut walled = Grid::new();
+    let ring = Bounds {
+        min_x: 4,
+        min_y: 4,
+        max_x: 6,
+        max_y: 6,
+    };
+    walled.fill_region(&ring, Belief::Occupied);
+    walled.information.insert(ACoord { x: 5, y: 5 }, Belief::Free);
+    let mut planner = RrtStarPlanner::new().with_iterations(300);
+    assert!(planner.plan(&walled, ACoord { x: 0, y: 0 }, ACoord { x: 5, y: 5 }).is_none());
+    assert!(planner.plan(&walled, ACoord { x: 0, y: 0 }, ACoord { x: 4, y: 5 }).is_none());
+    let plan = planner.plan(&walled, ACoord { x: 0, y: 0 }, ACoord { x: 8, y: 8 }).unwrap();
+    length(&walled, ACoord { x: 0, y: 0 }, &plan);
+}
+
+#[test]
+fn test_navigation() {
+    let oracle = read_grid("./data/sample/test_nav0.map").unwrap();
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+    let lidar = Lidar::new(100, oracle.clone());
+    let planner = RrtStarPlanner::new().with_seed(3);
+    let mut sabby = Sabrina::new(source, Grid::new(), lidar, planner);
+    assert_eq!(Status::Complete, sabby.navigate(target));
+    assert_eq!(sabby.position, target);
+}
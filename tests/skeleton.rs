@@ -0,0 +1,47 @@
+use sabrina::algo::skeleton::Skeleton;
+use sabrina::environment::grid::Grid;
+use sabrina::global::types::{ACoord, Belief, Bounds};
+use sabrina::parser::grid::read_grid;
+
+#[test]
+fn test_corridor_skeleton() {
+    let mut corridor = Grid::new();
+    for x in 0..=20 {
+        corridor.information.insert(ACoord { x, y: 0 }, Belief::Occupied);
+        corridor.information.insert(ACoord { x, y: 6 }, Belief::Occupied);
+    }
+    let bounds = Bounds {
+        min_x: 0,
+        min_y: 0,
+        max_x: 20,
+        max_y: 6,
+    };
+    let skeleton = Skeleton::extract(&corridor, &bounds);
+    assert_eq!(skeleton.cells.len(), 21);
+    assert!(skeleton.cells.iter().all(|(c, &d)| c.y == 3 && d == 3));
+    assert_eq!(skeleton.edges.len(), 1);
+    let edge = &skeleton.edges[0];
+    assert_eq!(edge.clearance, 3);
+    assert_eq!(edge.length, 20);
+    assert_eq!(edge.path.len(), 21);
+}
+
+#[test]
+fn test_map_skeleton() {
+    let path = "./data/sample/test_nav0.map";
+    match read_grid(path) {
+        Ok(oracle) => {
+            let skeleton = Skeleton::extract(&oracle, &oracle.bounds);
+            assert!(!skeleton.edges.is_empty());
+            for edge in &skeleton.edges {
+                assert!(edge.clearance > 0);
+                assert!(skeleton.nodes.contains(&edge.source));
+                assert!(skeleton.nodes.contains(&edge.target));
+                assert!(edge.path.iter().all(|c| !oracle.information.contains_key(c)));
+            }
+        }
+        Err(e) => {
+            panic!("Unexpected error in skeleton extraction\n{e:?}");
+        }
+    }
+}
@@ -0,0 +1,135 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::algo::d_star::DStarPlanner;
+use sabrina::algo::jps::JpsPlanner;
+use sabrina::algo::smoothing::{SmoothedPlanner, Smoother, merge_collinear, string_pull};
+use sabrina::environment::grid::Grid;
+use sabrina::global::line::{bresenham, supercover};
+use sabrina::global::types::plan::Planner;
+use sabrina::global::types::{ACoord, Belief, Connectivity, Opacity, PlanIter, SpatialMap, Status};
+use sabrina::intelligence::sabrina::Sabrina;
+use sabrina::parser::grid::read_grid;
+use sabrina::sensor::lidar::Lidar;
+
+/// Straight length of the waypoints, every segment seen clear
+fn length<S: SpatialMap>(env: &S, source: ACoord, waypoints: &[ACoord]) -> f64 {
+    let mut prev = source;
+    let mut total = 0.0;
+    for &w in waypoints {
+        assert!(env.line_of_sight(prev, w, Opacity::Transparent), "{prev:?} {w:?}");
+        total += ((w.x - prev.x) as f64).hypot((w.y - prev.y) as f64);
+        prev = w;
+    }
+    total
+}
+
+#[test]
+fn test_merge_collinear() {
+    let source = ACoord { x: 0, y: 0 };
+    let steps: Vec<ACoord> = [(1, 0), (2, 0), (3, 0), (4, 1), (5, 2), (5, 3), (7, 4), (9, 5)]
+        .into_iter()
+        .map(|(x, y)| ACoord { x, y })
+        .collect();
+    let merged = merge_collinear(source, &steps);
+    // slanted runs are kept apart, their straight walk may leave the original cells
+    let expected: Vec<ACoord> = [(3, 0), (5, 2), (5, 3), (7, 4), (9, 5)]
+        .into_iter()
+        .map(|(x, y)| ACoord { x, y })
+        .collect();
+    assert_eq!(merged, expected);
+    assert_eq!(merge_collinear(source, &[source, source]), vec![]);
+}
+
+#[test]
+fn test_pulled_plans_follow_the_cells() {
+    let grid = read_grid("./data/sample/test_nav0.map").unwrap();
+    for connectivity in [Connectivity::Four, Connectivity::Octile] {
+        let grid = grid.clone().with_connectivity(connectivity);
+        let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+        let a_star = AStarPlanner.plan(&grid, source, target).unwrap();
+        let raw: Vec<ACoord> = a_star.iter().copied().collect();
+        let merged = merge_collinear(source, &raw);
+        assert!(merged.len() < raw.len());
+        // merged segments step through exactly the cells of the plan
+        let mut walked = vec![];
+        let mut prev = source;
+        for &w in &merged {
+            walked.extend(bresenham(prev, w).into_iter().skip(1));
+            prev = w;
+        }
+        assert_eq!(walked, raw);
+        let pulled = string_pull(&grid, source, &merged, Opacity::Transparent);
+        assert!(pulled.len() <= merged.len());
+        assert_eq!(pulled.last(), Some(&target));
+        assert!(length(&grid, source, &pulled) <= length(&grid, source, &merged));
+    }
+}
+
+#[test]
+fn test_any_plan_direction() {
+    let grid = read_grid("./data/sample/test_nav0.map").unwrap();
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+    let smoother = Smoother::new();
+    let a_star = AStarPlanner.plan(&grid, source, target).unwrap();
+    let d_star = DStarPlanner::new().plan(&grid, source, target).unwrap();
+    let jps = JpsPlanner.plan(&grid, source, target).unwrap();
+    let backward = smoother.smooth(&grid, source, &a_star);
+    let forward = smoother.smooth(&grid, source, &d_star);
+    let jumps = smoother.smooth(&grid, source, &jps);
+    for plan in [&backward, &forward, &jumps] {
+        assert_eq!(plan.nodes().last(), Some(&target));
+        length(&grid, source, plan.nodes());
+    }
+}
+
+#[test]
+fn test_spline_clearance() {
+    let mut grid = Grid::new().with_connectivity(Connectivity::Octile);
+    for x in 0..30 {
+        for y in 0..30 {
+            let wall = x == 0 || y == 0 || x == 29 || y == 29 || (x == 15 && y < 20);
+            let belief = if wall { Belief::Occupied } else { Belief::Free };
+            grid.information.insert(ACoord { x, y }, belief);
+        }
+    }
+    let (source, target) = (ACoord { x: 3, y: 3 }, ACoord { x: 26, y: 3 });
+    let plan = AStarPlanner.plan(&grid, source, target).unwrap();
+    let pulled = Smoother::new().smooth(&grid, source, &plan);
+    for clearance in [0, 1, 2] {
+        let curved = Smoother::new().with_spline(8, clearance).smooth(&grid, source, &plan);
+        assert_eq!(curved.nodes().last(), Some(&target));
+        assert!(curved.nodes().len() >= pulled.nodes().len());
+        if clearance == 0 {
+            assert!(curved.nodes().len() > pulled.nodes().len());
+        }
+        length(&grid, source, curved.nodes());
+        // every cell along the curve keeps its distance from the walls
+        let r = clearance as isize;
+        let waypoint = |l: &ACoord| *l == source || pulled.nodes().contains(l);
+        let mut prev = source;
+        for &c in curved.nodes() {
+            let leg = supercover(prev, c);
+            let curve = !waypoint(&c) || !waypoint(&prev);
+            prev = c;
+            if !curve {
+                continue;
+            }
+            for l in leg.into_iter().filter(|l| !waypoint(l)) {
+                for (dx, dy) in (-r..=r).flat_map(|dx| (-r..=r).map(move |dy| (dx, dy))) {
+                    let n = ACoord { x: l.x + dx, y: l.y + dy };
+                    assert_ne!(grid.belief(n), Belief::Occupied, "{l:?} {clearance}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn test_smoothed_navigation() {
+    let oracle = read_grid("./data/sample/test_nav0.map").unwrap();
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+    let lidar = Lidar::new(100, oracle.clone());
+    let planner = SmoothedPlanner::new(AStarPlanner, Smoother::new().with_spline(6, 1));
+    let mut sabby = Sabrina::new(source, Grid::new(), lidar, planner);
+    assert_eq!(Status::Complete, sabby.navigate(target));
+    assert_eq!(sabby.position, target);
+}
@@ -0,0 +1,93 @@
+use sabrina::algo::a_star::AStarPlanner;
+use sabrina::algo::theta_star::ThetaStarPlanner;
+use sabrina::environment::grid::Grid;
+use sabrina::global::line::{bresenham, supercover};
+use sabrina::global::types::plan::Planner;
+use sabrina::global::types::{ACoord, Belief, Bounds, Connectivity, Opacity, PlanIter, SpatialMap, Status};
+use sabrina::intelligence::sabrina::Sabrina;
+use sabrina::parser::grid::read_grid;
+use sabrina::parser::quad::read_quad;
+use sabrina::sensor::lidar::Lidar;
+
+/// Euclidean length of the plan, every segment must be in sight
+fn length<S: SpatialMap>(env: &S, source: ACoord, corners: &[ACoord]) -> usize {
+    let mut prev = source;
+    let mut total = 0;
+    for &corner in corners {
+        assert!(env.line_of_sight(prev, corner, Opacity::Transparent), "{prev:?} -> {corner:?}");
+        total += Connectivity::Euclidean.cost(prev, corner);
+        prev = corner;
+    }
+    total
+}
+
+#[test]
+fn test_bresenham() {
+    let (a, b) = (ACoord { x: 3, y: 1 }, ACoord { x: -4, y: 4 });
+    let cells = bresenham(a, b);
+    assert_eq!(cells.len(), 8);
+    assert_eq!(cells.first(), Some(&a));
+    assert_eq!(cells.last(), Some(&b));
+    let cover = supercover(a, b);
+    assert!(cells.iter().all(|c| cover.contains(c)));
+    assert!(cells.windows(2).all(|w| w[0].x.abs_diff(w[1].x).max(w[0].y.abs_diff(w[1].y)) == 1));
+}
+
+#[test]
+fn test_theta_star() {
+    let path = "./data/sample/test_nav0.map";
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+    for connectivity in [Connectivity::Four, Connectivity::Octile] {
+        let grid = read_grid(path).unwrap().with_connectivity(connectivity);
+        let staircase = AStarPlanner.plan(&grid, source, target).unwrap();
+        let cells: Vec<ACoord> = staircase.iter().copied().collect();
+        let staircase_length = length(&grid, source, &cells);
+        for mut planner in [ThetaStarPlanner::new(), ThetaStarPlanner::lazy()] {
+            let plan = planner.plan(&grid, source, target).unwrap();
+            assert_eq!(plan.nodes().last(), Some(&target));
+            assert!(plan.nodes().len() < cells.len());
+            assert!(length(&grid, source, plan.nodes()) <= staircase_length);
+        }
+    }
+    let quad = read_quad(path, 3).unwrap();
+    let plan = ThetaStarPlanner::new().plan(&quad, source, target).unwrap();
+    length(&quad, source, plan.nodes());
+}
+
+#[test]
+fn test_open_field() {
+    let mut grid = Grid::new();
+    grid.fill_region(
+        &Bounds {
+            min_x: 0,
+            min_y: 0,
+            max_x: 9,
+            max_y: 9,
+        },
+        Belief::Free,
+    );
+    let (source, target) = (ACoord { x: 0, y: 0 }, ACoord { x: 7, y: 3 });
+    let plan = ThetaStarPlanner::new().plan(&grid, source, target).unwrap();
+    assert_eq!(plan.nodes(), &[target]);
+    // a wall forces a single corner
+    for y in 0..3 {
+        grid.information.insert(ACoord { x: 4, y }, Belief::Occupied);
+    }
+    for mut planner in [ThetaStarPlanner::new(), ThetaStarPlanner::lazy()] {
+        let plan = planner.plan(&grid, source, target).unwrap();
+        assert_eq!(plan.nodes().len(), 2);
+        length(&grid, source, plan.nodes());
+    }
+}
+
+#[test]
+fn test_theta_navigation() {
+    let oracle = read_grid("./data/sample/test_nav0.map").unwrap();
+    let (source, target) = (ACoord { x: 1, y: 1 }, ACoord { x: 18, y: 3 });
+    for planner in [ThetaStarPlanner::new(), ThetaStarPlanner::lazy()] {
+        let lidar = Lidar::new(100, oracle.clone());
+        let mut sabby = Sabrina::new(source, Grid::new(), lidar, planner);
+        assert_eq!(Status::Complete, sabby.navigate(target));
+        assert_eq!(sabby.position, target);
+    }
+}
@@ -0,0 +1,104 @@
+use sabrina::environment::grid::Grid;
+use sabrina::environment::quad::QuadTree;
+use sabrina::global::line::supercover;
+use sabrina::global::types::{ACoord, Belief, Bounds, COMPASS_ROSE, Opacity, SpatialMap};
+use sabrina::parser::grid::read_grid;
+use sabrina::parser::quad::read_quad;
+
+#[test]
+fn test_supercover() {
+    let cells = supercover(ACoord { x: 0, y: 0 }, ACoord { x: 2, y: 2 });
+    assert_eq!(cells.len(), 7);
+    assert!(cells.contains(&ACoord { x: 1, y: 0 }));
+    assert!(cells.contains(&ACoord { x: 0, y: 1 }));
+    let cells = supercover(ACoord { x: 3, y: 1 }, ACoord { x: -2, y: 0 });
+    assert_eq!(cells.first(), Some(&ACoord { x: 3, y: 1 }));
+    assert_eq!(cells.last(), Some(&ACoord { x: -2, y: 0 }));
+    assert!(cells.windows(2).all(|w| w[0].x.abs_diff(w[1].x).max(w[0].y.abs_diff(w[1].y)) == 1));
+}
+
+#[test]
+fn test_line_of_sight() {
+    let path = "./data/sample/test_nav0.map";
+    match (read_grid(path), read_quad(path, 3)) {
+        (Ok(grid), Ok(quad)) => {
+            let (a, b) = (ACoord { x: 1, y: 1 }, ACoord { x: 1, y: 3 });
+            assert!(grid.line_of_sight(a, b, Opacity::Transparent));
+            let c = ACoord { x: 18, y: 3 };
+            assert!(!grid.line_of_sight(a, c, Opacity::Transparent));
+            for x in 1..grid.bounds.max_x {
+                for y in 1..grid.bounds.max_y {
+                    let target = ACoord { x, y };
+                    assert_eq!(
+                        grid.line_of_sight(a, target, Opacity::Transparent),
+                        quad.line_of_sight(a, target, Opacity::Transparent),
+                    );
+                }
+            }
+        }
+        _ => {
+            panic!("Unexpected error in line of sight");
+        }
+    }
+}
+
+#[test]
+fn test_raycast_unknown() {
+    let path = "./data/sample/test_nav0.map";
+    match read_quad(path, 3) {
+        Ok(quad) => {
+            let origin = ACoord { x: 1, y: 1 };
+            let east = ACoord { x: 1, y: 0 };
+            let hit = quad.raycast(origin, east, 30, Opacity::Transparent);
+            assert_eq!(hit, Some(ACoord { x: 2, y: 1 }));
+            let north = ACoord { x: 0, y: 1 };
+            let hit = quad.raycast(ACoord { x: 18, y: 1 }, north, 30, Opacity::Transparent);
+            assert_eq!(hit, Some(ACoord { x: 18, y: 5 }));
+            // beyond the map the quadtree holds no belief
+            let outside = ACoord { x: 1, y: 9 };
+            assert_eq!(quad.raycast(outside, north, 30, Opacity::Transparent), None);
+            assert!(quad.raycast(outside, north, 30, Opacity::Opaque).is_some());
+        }
+        Err(e) => {
+            panic!("Unexpected error in raycast\n{e:?}");
+        }
+    }
+}
+
+#[test]
+fn test_quad_sight_skips_leaves() {
+    // leaves of every size, the grid walks the same lines cell by cell
+    let mut quad = QuadTree::init(5);
+    let whole = Bounds {
+        min_x: 0,
+        min_y: 0,
+        max_x: 15,
+        max_y: 15,
+    };
+    quad.fill_rect(&whole, Belief::Free);
+    let cells = [(5, 2, Belief::Occupied), (9, 9, Belief::Unknown), (12, 3, Belief::Occupied)];
+    for (x, y, belief) in cells {
+        quad.update_belief(&ACoord { x, y }, belief);
+    }
+    let block = Bounds {
+        min_x: 2,
+        min_y: 10,
+        max_x: 3,
+        max_y: 11,
+    };
+    quad.fill_rect(&block, Belief::Occupied);
+    let grid = Grid::from_map(&quad);
+    let cells: Vec<ACoord> = (0..16).flat_map(|x| (0..16).map(move |y| ACoord { x, y })).collect();
+    for &a in &cells {
+        for &b in &cells {
+            for unknown in [Opacity::Transparent, Opacity::Opaque] {
+                let sight = grid.line_of_sight(a, b, unknown);
+                assert_eq!(quad.line_of_sight(a, b, unknown), sight, "{a:?} {b:?}");
+            }
+        }
+        for direction in COMPASS_ROSE.into_iter().chain([ACoord { x: 2, y: -3 }]) {
+            let hit = grid.raycast(a, direction, 12, Opacity::Transparent);
+            assert_eq!(quad.raycast(a, direction, 12, Opacity::Transparent), hit, "{a:?}");
+        }
+    }
+}